@@ -19,7 +19,7 @@ def cmd_corridor_alloc(args):
         "lambda_nm": list(range(args.lambda_start, args.lambda_start+args.lanes)),
         "min_gbps": args.min_gbps, "latency_budget_ns": args.latency_ns,
         "reach_mm": args.reach_mm, "mode": "waveguide",
-        "qos": {"pfc": True, "priority": "gold"}, "attestation_required": True
+        "qos": {"pfc": True, "priority": "high"}, "attestation_required": True
     }
     print(json.dumps(post("/v1/corridors", payload), indent=2))
 
@@ -0,0 +1,33 @@
+//! Stamp the binary with the git commit and build time for `/v1/version`, and
+//! with the `grpc` feature, generate the server from `proto/corrd.proto`.
+
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    let sha = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let built_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    println!("cargo:rustc-env=CORRD_GIT_SHA={}", sha);
+    println!("cargo:rustc-env=CORRD_BUILT_AT={}", built_at);
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=../../.git/HEAD");
+    println!("cargo:rerun-if-changed=../../.git/refs/heads");
+
+    #[cfg(feature = "grpc")]
+    {
+        // The vendored protoc, so building doesn't need one installed.
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc for this platform");
+        std::env::set_var("PROTOC", protoc);
+        tonic_build::configure()
+            .build_client(false)
+            .compile_protos(&["proto/corrd.proto"], &["proto"])
+            .expect("compile proto/corrd.proto");
+    }
+}
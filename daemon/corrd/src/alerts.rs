@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+use crate::{Corridor, CorridorStatus, TelemetryData};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    BerHigh,
+    TempHigh,
+    PowerBudgetExceeded,
+    CorridorError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertState {
+    Firing,
+    Cleared,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub state: AlertState,
+    pub corridor_id: String,
+    pub value: f64,
+    pub threshold: f64,
+    pub ts: chrono::DateTime<chrono::Utc>,
+}
+
+/// Alert thresholds. An alert fires above its threshold and only clears once the
+/// value drops below `threshold * (1 - hysteresis)`, so it doesn't flap. While it
+/// stays firing it is re-sent every `repeat`, unless the corridor is acknowledged.
+#[derive(Debug, Clone)]
+pub struct AlertThresholds {
+    pub ber_high: f64,
+    pub temp_high_c: f64,
+    pub power_budget_mw: f64,
+    pub hysteresis: f64,
+    pub repeat: Duration,
+}
+
+impl AlertThresholds {
+    pub fn from_env() -> Self {
+        let get = |key: &str, default: f64| env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default);
+        Self {
+            ber_high: get("CORRD_ALERT_BER_HIGH", 1.0e-9),
+            temp_high_c: get("CORRD_ALERT_TEMP_HIGH_C", 75.0),
+            power_budget_mw: get("CORRD_ALERT_POWER_BUDGET_MW", 5000.0),
+            hysteresis: get("CORRD_ALERT_HYSTERESIS", 0.1),
+            repeat: Duration::from_millis(get("CORRD_ALERT_REPEAT_MS", 300000.0) as u64),
+        }
+    }
+}
+
+/// Where a reading sits against an alert's fire and clear levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Signal {
+    Fire,
+    Clear,
+    /// Inside the hysteresis band: keep whatever state the alert is in.
+    Hold,
+}
+
+pub struct AlertEngine {
+    thresholds: AlertThresholds,
+    /// Firing alerts and when each was last sent.
+    active: Mutex<HashMap<(String, AlertKind), chrono::DateTime<chrono::Utc>>>,
+    tx: broadcast::Sender<Alert>,
+}
+
+impl AlertEngine {
+    pub fn new(thresholds: AlertThresholds) -> Self {
+        let (tx, _) = broadcast::channel(256);
+        Self { thresholds, active: Mutex::new(HashMap::new()), tx }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Alert> {
+        self.tx.subscribe()
+    }
+
+    /// Evaluate one telemetry refresh for `corridor` taken at `now` (the service
+    /// clock), emitting fire/clear transitions.
+    pub fn evaluate(&self, corridor: &Corridor, telem: &TelemetryData, now: chrono::DateTime<chrono::Utc>) {
+        let t = &self.thresholds;
+        let power_mw = telem.power_pj_per_bit * corridor.achievable_gbps as f64;
+        let is_error = matches!(corridor.status, CorridorStatus::Error);
+        self.check(corridor, now, AlertKind::BerHigh, telem.ber, t.ber_high);
+        self.check(corridor, now, AlertKind::TempHigh, telem.temp_c, t.temp_high_c);
+        self.check(corridor, now, AlertKind::PowerBudgetExceeded, power_mw, t.power_budget_mw);
+        let signal = if is_error { Signal::Fire } else { Signal::Clear };
+        self.transition(corridor, now, AlertKind::CorridorError, signal, if is_error { 1.0 } else { 0.0 }, 1.0);
+    }
+
+    fn check(&self, corridor: &Corridor, now: chrono::DateTime<chrono::Utc>, kind: AlertKind, value: f64, threshold: f64) {
+        let signal = if value > threshold {
+            Signal::Fire
+        } else if value < threshold * (1.0 - self.thresholds.hysteresis) {
+            Signal::Clear
+        } else {
+            Signal::Hold
+        };
+        self.transition(corridor, now, kind, signal, value, threshold);
+    }
+
+    fn transition(&self, corridor: &Corridor, now: chrono::DateTime<chrono::Utc>, kind: AlertKind, signal: Signal, value: f64, threshold: f64) {
+        let key = (corridor.id.clone(), kind);
+        let fire = signal == Signal::Fire;
+        let state = {
+            let mut active = self.active.lock().unwrap();
+            match active.get(&key).copied() {
+                None if fire => {
+                    active.insert(key, now);
+                    if corridor.acked {
+                        return;
+                    }
+                    AlertState::Firing
+                }
+                Some(sent) if fire && !corridor.acked && (now - sent).to_std().unwrap_or_default() >= self.thresholds.repeat => {
+                    active.insert(key, now);
+                    AlertState::Firing
+                }
+                Some(_) if signal == Signal::Clear => {
+                    active.remove(&key);
+                    AlertState::Cleared
+                }
+                _ => return,
+            }
+        };
+        // No subscribers is fine; the alert is simply not delivered.
+        let _ = self.tx.send(Alert {
+            kind,
+            state,
+            corridor_id: corridor.id.clone(),
+            value,
+            threshold,
+            ts: now,
+        });
+    }
+}
@@ -0,0 +1,181 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::http::{parse_base_url, BaseUrl, Scheme};
+
+/// Outcome of checking an attestation ticket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    Deny,
+}
+
+/// A decision together with when the ticket stops being valid, if the backend says.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Verdict {
+    pub decision: Decision,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Verdict {
+    pub const DENY: Verdict = Verdict { decision: Decision::Deny, expires_at: None };
+
+    pub fn allow(expires_at: Option<DateTime<Utc>>) -> Self {
+        Self { decision: Decision::Allow, expires_at }
+    }
+
+    /// Allowed and not expired at `now`.
+    pub fn allows_at(&self, now: DateTime<Utc>) -> bool {
+        self.decision == Decision::Allow && self.expires_at.is_none_or(|t| t > now)
+    }
+}
+
+/// Backend that decides whether an attestation ticket is acceptable.
+pub trait AttestationVerifier: Send + Sync {
+    fn verify(&self, ticket: &str) -> Result<Verdict>;
+}
+
+/// `ATTESTD_URL`, default `http://localhost:8084`.
+pub fn attestd_url_from_env() -> String {
+    env::var("ATTESTD_URL").unwrap_or_else(|_| "http://localhost:8084".to_string())
+}
+
+/// Verifier backed by the attestd HTTP service (`GET /v1/attest/{ticket}`).
+pub struct AttestdVerifier {
+    url: BaseUrl,
+    timeout: Duration,
+    /// Dedicated TLS settings (private CA and/or client certificate); `None` uses
+    /// the shared client.
+    tls: Option<rustls::ClientConfig>,
+    client: OnceLock<&'static reqwest::blocking::Client>,
+}
+
+impl AttestdVerifier {
+    pub fn new(url: BaseUrl, timeout: Duration) -> Self {
+        Self { url, timeout, tls: None, client: OnceLock::new() }
+    }
+
+    /// `ATTESTD_URL` with `ATTESTD_TIMEOUT_MS` (default 2000). `ATTESTD_CA_CERT` pins
+    /// the CA for attestd, and `ATTESTD_CLIENT_CERT`/`ATTESTD_CLIENT_KEY` enable
+    /// mutual TLS; either requires an `https://` URL.
+    pub fn from_env() -> Result<Self> {
+        let url = parse_base_url(&attestd_url_from_env()).map_err(|e| anyhow::anyhow!("ATTESTD_URL: {}", e))?;
+        let mut verifier = Self::new(url, crate::http::timeout_from_env("ATTESTD_TIMEOUT_MS"));
+        let path = |key: &str| env::var(key).ok().filter(|v| !v.is_empty());
+        let ca = path("ATTESTD_CA_CERT");
+        let identity = match (path("ATTESTD_CLIENT_CERT"), path("ATTESTD_CLIENT_KEY")) {
+            (Some(cert), Some(key)) => Some((cert, key)),
+            (None, None) => None,
+            _ => return Err(anyhow::anyhow!("ATTESTD_CLIENT_CERT and ATTESTD_CLIENT_KEY must be set together")),
+        };
+        if ca.is_none() && identity.is_none() {
+            return Ok(verifier);
+        }
+        if verifier.url.scheme != Scheme::Https {
+            return Err(anyhow::anyhow!("ATTESTD_CA_CERT/ATTESTD_CLIENT_* need an https:// ATTESTD_URL, got {}", verifier.url));
+        }
+        let policy = crate::tls::TlsPolicy::from_env()?;
+        let identity = identity.as_ref().map(|(c, k)| (c.as_str(), k.as_str()));
+        verifier.tls = Some(crate::tls::client_config(&policy, ca.as_deref(), identity)?);
+        Ok(verifier)
+    }
+
+    /// The dedicated client, built on first use because blocking clients must be
+    /// created off the async runtime, and leaked so it is never dropped on it either.
+    fn tls_client(&self, tls: &rustls::ClientConfig) -> Result<&'static reqwest::blocking::Client> {
+        if let Some(c) = self.client.get() {
+            return Ok(c);
+        }
+        let built: &'static reqwest::blocking::Client = Box::leak(Box::new(crate::http::build_client(tls.clone())?));
+        Ok(self.client.get_or_init(|| built))
+    }
+}
+
+impl AttestationVerifier for AttestdVerifier {
+    fn verify(&self, ticket: &str) -> Result<Verdict> {
+        let url = self.url.join(&format!("/v1/attest/{}", ticket));
+        let v: serde_json::Value = match &self.tls {
+            None => crate::http::get_json(&url, self.timeout)?,
+            Some(tls) => match crate::http::get_json_with(self.tls_client(tls)?, &url, self.timeout) {
+                Ok(v) => v,
+                // With pinned TLS configured, failing to complete the exchange at all
+                // (unreachable, handshake failed, certificate rejected) fails closed as
+                // a denied ticket rather than a downstream error.
+                Err(e) if crate::http::is_transport_error(&e) => {
+                    tracing::warn!("attestd TLS connection failed, denying ticket: {:#}", e);
+                    return Ok(Verdict::DENY);
+                }
+                Err(e) => return Err(e),
+            },
+        };
+        let valid = v.get("valid").and_then(|x| x.as_bool()).unwrap_or(false);
+        if !valid {
+            return Ok(Verdict::DENY);
+        }
+        // `expires_at` is optional (RFC 3339); a ticket without one never expires.
+        let expires_at = match v.get("expires_at").and_then(|x| x.as_str()) {
+            Some(s) => Some(
+                DateTime::parse_from_rfc3339(s)
+                    .map_err(|e| anyhow::anyhow!("attestd returned invalid expires_at '{}': {}", s, e))?
+                    .with_timezone(&Utc),
+            ),
+            None => None,
+        };
+        Ok(Verdict::allow(expires_at))
+    }
+}
+
+/// Recent verdicts keyed by ticket. Denials are kept for a shorter window than
+/// approvals so a ticket rejected during an attestd hiccup recovers quickly, and
+/// an approval is never kept past the ticket's own expiry; verifier errors are
+/// never cached.
+pub struct AttestationCache {
+    ttl: Duration,
+    negative_ttl: Duration,
+    entries: Mutex<HashMap<String, (Verdict, DateTime<Utc>)>>,
+}
+
+impl AttestationCache {
+    /// `ATTEST_CACHE_TTL_MS` (default 30000) and `ATTEST_CACHE_NEGATIVE_TTL_MS` (default 5000).
+    pub fn from_env() -> Self {
+        let get = |key: &str, default: u64| env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default);
+        Self {
+            ttl: Duration::from_millis(get("ATTEST_CACHE_TTL_MS", 30000)),
+            negative_ttl: Duration::from_millis(get("ATTEST_CACHE_NEGATIVE_TTL_MS", 5000)),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The cached verdict for `ticket`, if still fresh at `now` (the service clock).
+    pub fn get(&self, ticket: &str, now: DateTime<Utc>) -> Option<Verdict> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(ticket).filter(|(_, expires_at)| now < *expires_at).map(|(d, _)| *d)
+    }
+
+    pub fn insert(&self, ticket: &str, verdict: Verdict, now: DateTime<Utc>) {
+        let mut ttl = if verdict.decision == Decision::Allow { self.ttl } else { self.negative_ttl };
+        if let Some(expires_at) = verdict.expires_at {
+            ttl = ttl.min((expires_at - now).to_std().unwrap_or_default());
+        }
+        if ttl.is_zero() {
+            return;
+        }
+        let Ok(ttl) = chrono::Duration::from_std(ttl) else { return };
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (_, expires_at)| now < *expires_at);
+        entries.insert(ticket.to_string(), (verdict, now + ttl));
+    }
+}
+
+/// Build the verifier selected by `CORRD_ATTESTATION_VERIFIER` (default `attestd`).
+pub fn verifier_from_env() -> Result<Box<dyn AttestationVerifier>> {
+    let backend = env::var("CORRD_ATTESTATION_VERIFIER").unwrap_or_else(|_| "attestd".to_string());
+    match backend.as_str() {
+        "attestd" => Ok(Box::new(AttestdVerifier::from_env()?)),
+        other => Err(anyhow::anyhow!("unknown attestation verifier '{}', expected one of: attestd", other)),
+    }
+}
@@ -0,0 +1,76 @@
+use std::env;
+use std::sync::Arc;
+use warp::path::FullPath;
+use warp::Filter;
+
+use crate::error::CorridorError;
+
+/// Optional bearer tokens: `CORRD_API_TOKEN` guards `/v1/*`, `CORRD_METRICS_TOKEN`
+/// guards `/metrics`. Unset means open; `/health` and `/ready` are never guarded.
+#[derive(Debug, Clone, Default)]
+pub struct AuthConfig {
+    api_token: Option<String>,
+    metrics_token: Option<String>,
+}
+
+impl AuthConfig {
+    pub fn from_env() -> Self {
+        let get = |key: &str| env::var(key).ok().filter(|v| !v.is_empty());
+        Self { api_token: get("CORRD_API_TOKEN"), metrics_token: get("CORRD_METRICS_TOKEN") }
+    }
+
+    fn token_for(&self, path: &str) -> Option<&str> {
+        if path == "/v1" || path.starts_with("/v1/") {
+            self.api_token.as_deref()
+        } else if path == "/metrics" {
+            self.metrics_token.as_deref()
+        } else {
+            None
+        }
+    }
+}
+
+/// Compare without short-circuiting on the first differing byte.
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len() && given.bytes().zip(expected.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Whether an `Authorization` value carries `expected` as its bearer token.
+fn bearer_matches(header: Option<&str>, expected: &str) -> bool {
+    header.and_then(|h| h.strip_prefix("Bearer ")).is_some_and(|given| tokens_match(given.trim(), expected))
+}
+
+impl AuthConfig {
+    /// The bearer token from `header` when `path` requires a token and this is it;
+    /// `None` on open paths and for anything that didn't authenticate.
+    pub fn verified_token<'a>(&self, path: &str, header: Option<&'a str>) -> Option<&'a str> {
+        let expected = self.token_for(path)?;
+        let given = header?.strip_prefix("Bearer ")?.trim();
+        tokens_match(given, expected).then_some(given)
+    }
+
+    /// The `/v1` check for callers outside warp (the gRPC server).
+    #[cfg(feature = "grpc")]
+    pub fn api_authorized(&self, header: Option<&str>) -> bool {
+        self.api_token.as_deref().is_none_or(|expected| bearer_matches(header, expected))
+    }
+}
+
+/// Filter placed in front of every route; rejects with `CorridorError::Unauthorized`
+/// when the path needs a token and the `Authorization: Bearer` header doesn't match.
+pub fn authorize(config: Arc<AuthConfig>) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::path::full()
+        .and(warp::header::optional::<String>("authorization"))
+        .and_then(move |path: FullPath, header: Option<String>| {
+            let config = config.clone();
+            async move {
+                let Some(expected) = config.token_for(path.as_str()) else { return Ok(()) };
+                if bearer_matches(header.as_deref(), expected) {
+                    Ok(())
+                } else {
+                    Err(warp::reject::custom(CorridorError::Unauthorized))
+                }
+            }
+        })
+        .untuple_one()
+}
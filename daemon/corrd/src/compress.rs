@@ -0,0 +1,57 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::env;
+use std::io::Write;
+use std::sync::OnceLock;
+use warp::http::header::{CONTENT_ENCODING, CONTENT_TYPE, VARY};
+use warp::http::HeaderValue;
+use warp::reply::Response;
+
+/// Bodies below `CORRD_GZIP_MIN_BYTES` (default 1024) go out as-is; gzip framing
+/// and CPU aren't worth it for a few hundred bytes.
+fn min_bytes() -> usize {
+    static MIN: OnceLock<usize> = OnceLock::new();
+    *MIN.get_or_init(|| env::var("CORRD_GZIP_MIN_BYTES").ok().and_then(|v| v.parse().ok()).unwrap_or(1024))
+}
+
+/// Whether `Accept-Encoding` allows gzip: an explicit `gzip` entry decides by its
+/// q-value, otherwise a `*` entry does.
+fn accepts_gzip(header: Option<&str>) -> bool {
+    let (mut gzip, mut any) = (None, None);
+    for item in header.unwrap_or_default().split(',') {
+        let mut parts = item.split(';').map(str::trim);
+        let coding = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let q = parts
+            .filter_map(|p| p.strip_prefix("q="))
+            .find_map(|q| q.parse::<f64>().ok())
+            .unwrap_or(1.0);
+        match coding.as_str() {
+            "gzip" | "x-gzip" => gzip = Some(q),
+            "*" => any = Some(q),
+            _ => {}
+        }
+    }
+    gzip.or(any).is_some_and(|q| q > 0.0)
+}
+
+/// A 200 response carrying `body`, gzipped when the client accepts it and the
+/// body is large enough.
+pub fn reply(body: Vec<u8>, content_type: &str, accept_encoding: Option<&str>) -> Response {
+    let compressed = (body.len() >= min_bytes() && accepts_gzip(accept_encoding))
+        .then(|| {
+            let mut encoder = GzEncoder::new(Vec::with_capacity(body.len() / 4), Compression::default());
+            encoder.write_all(&body).and_then(|_| encoder.finish()).ok()
+        })
+        .flatten();
+    let gzipped = compressed.is_some();
+    let mut response = Response::new(compressed.unwrap_or(body).into());
+    let headers = response.headers_mut();
+    if let Ok(v) = HeaderValue::from_str(content_type) {
+        headers.insert(CONTENT_TYPE, v);
+    }
+    headers.insert(VARY, HeaderValue::from_static("accept-encoding"));
+    if gzipped {
+        headers.insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+    }
+    response
+}
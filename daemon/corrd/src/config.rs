@@ -0,0 +1,107 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::env;
+
+/// Declares `Config` with one optional field per tunable and the env var it
+/// stands in for, so the two lists can't drift apart.
+macro_rules! tunables {
+    ($($field:ident: $ty:ty => $var:literal,)*) => {
+        /// Settings from the `--config`/`CORRD_CONFIG` file (TOML or JSON, by
+        /// extension). Every key is optional; unknown keys are an error.
+        #[derive(Debug, Default, Deserialize)]
+        #[serde(deny_unknown_fields)]
+        pub struct Config {
+            $(pub $field: Option<$ty>,)*
+        }
+
+        impl Config {
+            /// Export each value set in the file to its env var unless that var is
+            /// already set, so precedence is env, then file, then built-in default.
+            pub fn apply_to_env(&self) {
+                $(
+                    if let Some(value) = &self.$field {
+                        if env::var_os($var).is_none() {
+                            env::set_var($var, value.to_string());
+                        }
+                    }
+                )*
+            }
+        }
+    };
+}
+
+tunables! {
+    bind_addr: String => "CORRD_BIND_ADDR",
+    port: u16 => "CORRD_PORT",
+    grpc_port: u16 => "CORRD_GRPC_PORT",
+    env: String => "CORRD_ENV",
+    log_format: String => "CORRD_LOG_FORMAT",
+    state_path: String => "CORRD_STATE_PATH",
+    bootstrap_corridors: String => "CORRD_BOOTSTRAP_CORRIDORS",
+    receipt_key_path: String => "CORRD_RECEIPT_KEY_PATH",
+    tls_cert: String => "CORRD_TLS_CERT",
+    tls_key: String => "CORRD_TLS_KEY",
+    tls_min_version: String => "CORRD_TLS_MIN_VERSION",
+    tls_ciphers: String => "CORRD_TLS_CIPHERS",
+    max_body_bytes: u64 => "CORRD_MAX_BODY_BYTES",
+    gzip_min_bytes: usize => "CORRD_GZIP_MIN_BYTES",
+    http2_max_streams: u32 => "CORRD_HTTP2_MAX_STREAMS",
+    http2_keepalive_ms: u64 => "CORRD_HTTP2_KEEPALIVE_MS",
+    heliopass_url: String => "HELIOPASS_URL",
+    heliopass_timeout_ms: u64 => "HELIOPASS_TIMEOUT_MS",
+    heliopass_retry_attempts: u32 => "HELIOPASS_RETRY_ATTEMPTS",
+    heliopass_retry_backoff_ms: u64 => "HELIOPASS_RETRY_BACKOFF_MS",
+    attestation_verifier: String => "CORRD_ATTESTATION_VERIFIER",
+    attestd_url: String => "ATTESTD_URL",
+    attestd_timeout_ms: u64 => "ATTESTD_TIMEOUT_MS",
+    attestd_ca_cert: String => "ATTESTD_CA_CERT",
+    attestd_client_cert: String => "ATTESTD_CLIENT_CERT",
+    attestd_client_key: String => "ATTESTD_CLIENT_KEY",
+    attestation_check_interval_ms: u64 => "CORRD_ATTESTATION_CHECK_INTERVAL_MS",
+    attest_cache_ttl_ms: u64 => "ATTEST_CACHE_TTL_MS",
+    attest_cache_negative_ttl_ms: u64 => "ATTEST_CACHE_NEGATIVE_TTL_MS",
+    health_timeout_ms: u64 => "CORRD_HEALTH_TIMEOUT_MS",
+    degraded_p95_ms: u64 => "CORRD_DEGRADED_P95_MS",
+    http_pool_max_idle: usize => "CORRD_HTTP_POOL_MAX_IDLE",
+    latency_window: usize => "CORRD_LATENCY_WINDOW",
+    reconcile_interval_ms: u64 => "CORRD_RECONCILE_INTERVAL_MS",
+    power_budget_mw: f64 => "CORRD_POWER_BUDGET_MW",
+    rate_margin: f64 => "CORRD_RATE_MARGIN",
+    max_reach_mm_si: u32 => "CORRD_MAX_REACH_MM_SI",
+    max_reach_mm_carbon: u32 => "CORRD_MAX_REACH_MM_CARBON",
+    sim_seed: u64 => "CORRD_SIM_SEED",
+    idempotency_ttl_ms: u64 => "CORRD_IDEMPOTENCY_TTL_MS",
+    metric_labels: String => "CORRD_METRIC_LABELS",
+    metric_prefix: String => "CORRD_METRIC_PREFIX",
+    rate_limit_rps: f64 => "CORRD_RATE_LIMIT_RPS",
+    rate_limit_burst: f64 => "CORRD_RATE_LIMIT_BURST",
+    read_rate_limit_rps: f64 => "CORRD_READ_RATE_LIMIT_RPS",
+    read_rate_limit_burst: f64 => "CORRD_READ_RATE_LIMIT_BURST",
+    recalibration_history: usize => "CORRD_RECALIBRATION_HISTORY",
+    telemetry_window: usize => "CORRD_TELEMETRY_WINDOW",
+    max_down_lane_fraction: f64 => "CORRD_MAX_DOWN_LANE_FRACTION",
+    alert_ber_high: f64 => "CORRD_ALERT_BER_HIGH",
+    alert_temp_high_c: f64 => "CORRD_ALERT_TEMP_HIGH_C",
+    alert_power_budget_mw: f64 => "CORRD_ALERT_POWER_BUDGET_MW",
+    alert_hysteresis: f64 => "CORRD_ALERT_HYSTERESIS",
+    alert_repeat_ms: u64 => "CORRD_ALERT_REPEAT_MS",
+    admission_webhook: String => "CORRD_ADMISSION_WEBHOOK",
+    admission_fail_open: bool => "CORRD_ADMISSION_FAIL_OPEN",
+    post_allocate_webhook: String => "CORRD_POST_ALLOCATE_WEBHOOK",
+    webhook_url: String => "CORRD_WEBHOOK_URL",
+    webhook_timeout_ms: u64 => "CORRD_WEBHOOK_TIMEOUT_MS",
+    webhook_retry_attempts: u32 => "CORRD_WEBHOOK_RETRY_ATTEMPTS",
+    webhook_retry_backoff_ms: u64 => "CORRD_WEBHOOK_RETRY_BACKOFF_MS",
+}
+
+impl Config {
+    /// Read and validate `path`. Secrets (`CORRD_API_TOKEN`, `CORRD_METRICS_TOKEN`)
+    /// stay env-only.
+    pub fn load(path: &str) -> Result<Self> {
+        ::config::Config::builder()
+            .add_source(::config::File::with_name(path))
+            .build()
+            .and_then(|c| c.try_deserialize())
+            .map_err(|e| anyhow::anyhow!("config file {}: {}", path, e))
+    }
+}
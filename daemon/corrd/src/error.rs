@@ -0,0 +1,73 @@
+use warp::http::StatusCode;
+
+/// Service errors with a fixed HTTP mapping. Service methods still return
+/// `anyhow::Result`; anything that isn't one of these is treated as a bad request.
+#[derive(Debug, thiserror::Error)]
+pub enum CorridorError {
+    #[error("Corridor {0} not found")]
+    NotFound(String),
+    #[error("attestation required but no ticket provided")]
+    AttestationRequired,
+    #[error("attestation ticket invalid or expired")]
+    AttestationInvalid,
+    #[error("{0}")]
+    InvalidRequest(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    Downstream(String),
+    #[error("missing or invalid bearer token")]
+    Unauthorized,
+    #[error("{0}")]
+    InsufficientCapacity(String),
+    /// Seconds until the caller's bucket has a token again.
+    #[error("rate limit exceeded, retry in {0}s")]
+    RateLimited(u64),
+}
+
+impl warp::reject::Reject for CorridorError {}
+
+impl CorridorError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            CorridorError::NotFound(_) => StatusCode::NOT_FOUND,
+            CorridorError::AttestationRequired => StatusCode::UNAUTHORIZED,
+            CorridorError::AttestationInvalid => StatusCode::FORBIDDEN,
+            CorridorError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            CorridorError::Conflict(_) => StatusCode::CONFLICT,
+            CorridorError::Downstream(_) => StatusCode::BAD_GATEWAY,
+            CorridorError::Unauthorized => StatusCode::UNAUTHORIZED,
+            CorridorError::InsufficientCapacity(_) => StatusCode::INSUFFICIENT_STORAGE,
+            CorridorError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    /// Stable machine-readable name, returned as `code` next to `error`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            CorridorError::NotFound(_) => "not_found",
+            CorridorError::AttestationRequired => "attestation_required",
+            CorridorError::AttestationInvalid => "attestation_invalid",
+            CorridorError::InvalidRequest(_) => "invalid_request",
+            CorridorError::Conflict(_) => "conflict",
+            CorridorError::Downstream(_) => "downstream",
+            CorridorError::Unauthorized => "unauthorized",
+            CorridorError::InsufficientCapacity(_) => "insufficient_capacity",
+            CorridorError::RateLimited(_) => "rate_limited",
+        }
+    }
+}
+
+impl From<anyhow::Error> for CorridorError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<CorridorError>() {
+            Ok(err) => err,
+            Err(e) => CorridorError::InvalidRequest(e.to_string()),
+        }
+    }
+}
+
+/// Reject with the `CorridorError` behind `e`, for the recovery handler to render.
+pub fn reject(e: anyhow::Error) -> warp::Rejection {
+    warp::reject::custom(CorridorError::from(e))
+}
@@ -0,0 +1,309 @@
+use anyhow::Result;
+use std::env;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::auth::AuthConfig;
+use crate::error::CorridorError;
+use crate::link::{Modulation, OptimizeFor};
+use crate::{
+    anonymous_user, CalibrationSource, Corridor, CorridorRequest, CorridorService, CorridorStatus, CorridorType,
+    LaneState, ListQuery, Priority, QoSSettings, RecalibrateRequest, RecalibrateResponse, TelemetryData,
+};
+
+pub mod pb {
+    tonic::include_proto!("corrd.v1");
+}
+
+use pb::corridors_server::{Corridors, CorridorsServer};
+
+/// Same mapping as the HTTP statuses in `CorridorError::status`.
+fn status_from(e: impl Into<CorridorError>) -> Status {
+    let e = e.into();
+    let code = match &e {
+        CorridorError::NotFound(_) => tonic::Code::NotFound,
+        CorridorError::AttestationRequired | CorridorError::Unauthorized => tonic::Code::Unauthenticated,
+        CorridorError::AttestationInvalid => tonic::Code::PermissionDenied,
+        CorridorError::InvalidRequest(_) => tonic::Code::InvalidArgument,
+        CorridorError::Conflict(_) => tonic::Code::FailedPrecondition,
+        CorridorError::Downstream(_) => tonic::Code::Unavailable,
+        CorridorError::InsufficientCapacity(_) | CorridorError::RateLimited(_) => tonic::Code::ResourceExhausted,
+    };
+    Status::new(code, e.to_string())
+}
+
+fn timestamp(t: &chrono::DateTime<chrono::Utc>) -> String {
+    t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+fn invalid(message: impl Into<String>) -> CorridorError {
+    CorridorError::InvalidRequest(message.into())
+}
+
+fn corridor_type_from(v: i32) -> Result<CorridorType, CorridorError> {
+    match pb::CorridorType::try_from(v) {
+        Ok(pb::CorridorType::Si) => Ok(CorridorType::SiCorridor),
+        Ok(pb::CorridorType::Carbon) => Ok(CorridorType::CarbonCorridor),
+        _ => Err(invalid("corridor_type is required")),
+    }
+}
+
+fn priority_from(v: i32) -> Result<Priority, CorridorError> {
+    match pb::Priority::try_from(v) {
+        Ok(pb::Priority::Low) => Ok(Priority::Low),
+        Ok(pb::Priority::Normal) => Ok(Priority::Normal),
+        Ok(pb::Priority::High) => Ok(Priority::High),
+        Ok(pb::Priority::Critical) => Ok(Priority::Critical),
+        _ => Err(invalid("qos.priority is required")),
+    }
+}
+
+fn optimize_from(v: i32) -> Result<OptimizeFor, CorridorError> {
+    match pb::OptimizeFor::try_from(v) {
+        Ok(pb::OptimizeFor::Unspecified | pb::OptimizeFor::Throughput) => Ok(OptimizeFor::Throughput),
+        Ok(pb::OptimizeFor::Power) => Ok(OptimizeFor::Power),
+        Ok(pb::OptimizeFor::Balanced) => Ok(OptimizeFor::Balanced),
+        Err(_) => Err(invalid(format!("unknown optimize: {}", v))),
+    }
+}
+
+impl TryFrom<pb::CorridorRequest> for CorridorRequest {
+    type Error = CorridorError;
+
+    fn try_from(r: pb::CorridorRequest) -> Result<Self, CorridorError> {
+        let qos = r.qos.ok_or_else(|| invalid("qos is required"))?;
+        Ok(CorridorRequest {
+            corridor_type: corridor_type_from(r.corridor_type)?,
+            lanes: r.lanes,
+            lambda_nm: r.lambda_nm,
+            lambdas_per_lane: r.lambdas_per_lane,
+            min_gbps: r.min_gbps,
+            latency_budget_ns: r.latency_budget_ns,
+            reach_mm: r.reach_mm,
+            mode: r.mode,
+            qos: QoSSettings { pfc: qos.pfc, priority: priority_from(qos.priority)? },
+            attestation_required: r.attestation_required,
+            attestation_ticket: r.attestation_ticket,
+            optimize: optimize_from(r.optimize)?,
+            max_ber: r.max_ber,
+            medium_id: r.medium_id,
+            labels: r.labels.into_iter().collect(),
+        })
+    }
+}
+
+impl From<&Corridor> for pb::Corridor {
+    fn from(c: &Corridor) -> Self {
+        let corridor_type = match c.corridor_type {
+            CorridorType::SiCorridor => pb::CorridorType::Si,
+            CorridorType::CarbonCorridor => pb::CorridorType::Carbon,
+        };
+        let priority = match c.qos.priority {
+            Priority::Low => pb::Priority::Low,
+            Priority::Normal => pb::Priority::Normal,
+            Priority::High => pb::Priority::High,
+            Priority::Critical => pb::Priority::Critical,
+        };
+        let optimize = match c.optimize {
+            OptimizeFor::Throughput => pb::OptimizeFor::Throughput,
+            OptimizeFor::Power => pb::OptimizeFor::Power,
+            OptimizeFor::Balanced => pb::OptimizeFor::Balanced,
+        };
+        let modulation = match c.modulation {
+            Modulation::Nrz => pb::Modulation::Nrz,
+            Modulation::Pam4 => pb::Modulation::Pam4,
+        };
+        let status = match c.status {
+            CorridorStatus::Active => pb::CorridorStatus::Active,
+            CorridorStatus::Calibrating => pb::CorridorStatus::Calibrating,
+            CorridorStatus::Error => pb::CorridorStatus::Error,
+            CorridorStatus::Maintenance => pb::CorridorStatus::Maintenance,
+            CorridorStatus::AttestationExpired => pb::CorridorStatus::AttestationExpired,
+            CorridorStatus::Decommissioned => pb::CorridorStatus::Decommissioned,
+        };
+        let lane_status = c
+            .lane_status
+            .iter()
+            .map(|s| match s {
+                LaneState::Up => pb::LaneState::Up,
+                LaneState::Degraded => pb::LaneState::Degraded,
+                LaneState::Down => pb::LaneState::Down,
+            } as i32)
+            .collect();
+        pb::Corridor {
+            id: c.id.clone(),
+            corridor_type: corridor_type as i32,
+            lanes: c.lanes,
+            lambda_nm: c.lambda_nm.clone(),
+            lambdas_per_lane: c.lambdas_per_lane,
+            min_gbps: c.min_gbps,
+            latency_budget_ns: c.latency_budget_ns,
+            reach_mm: c.reach_mm,
+            mode: c.mode.clone(),
+            qos: Some(pb::QoSSettings { pfc: c.qos.pfc, priority: priority as i32 }),
+            attestation_required: c.attestation_required,
+            optimize: optimize as i32,
+            max_ber: c.max_ber,
+            medium_id: c.medium_id.clone(),
+            labels: c.labels.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            modulation: modulation as i32,
+            lane_rate_gbps: c.lane_rate_gbps,
+            fec: c.fec,
+            est_power_pj_per_bit: c.est_power_pj_per_bit,
+            total_power_mw: c.total_power_mw,
+            achievable_gbps: c.achievable_gbps,
+            planned_gbps: c.planned_gbps,
+            rate_margin: c.rate_margin,
+            ber: c.ber,
+            eye_margin: c.eye_margin.clone(),
+            eye_margin_value: c.eye_margin_value,
+            created_at: timestamp(&c.created_at),
+            status: status as i32,
+            status_since: timestamp(&c.status_since),
+            acked: c.acked,
+            bootstrap: c.bootstrap,
+            created_by: c.created_by.clone(),
+            receipt: c.receipt.clone(),
+            last_recalibrated_at: c.last_recalibrated_at.as_ref().map(timestamp),
+            attestation_expires_at: c.attestation_expires_at.as_ref().map(timestamp),
+            lane_status,
+        }
+    }
+}
+
+impl From<TelemetryData> for pb::TelemetryData {
+    fn from(t: TelemetryData) -> Self {
+        pb::TelemetryData {
+            ber: t.ber,
+            temp_c: t.temp_c,
+            power_pj_per_bit: t.power_pj_per_bit,
+            drift: t.drift,
+            utilization_percent: t.utilization_percent,
+            error_count: t.error_count,
+        }
+    }
+}
+
+impl From<RecalibrateResponse> for pb::RecalibrateResponse {
+    fn from(r: RecalibrateResponse) -> Self {
+        let source = match r.source {
+            CalibrationSource::Heliopass => pb::CalibrationSource::Heliopass,
+            CalibrationSource::Fallback => pb::CalibrationSource::Fallback,
+        };
+        pb::RecalibrateResponse {
+            status: r.status,
+            converged: r.converged,
+            bias_voltages: r.bias_voltages,
+            lambda_shifts: r.lambda_shifts,
+            laser_power_adjust: r.laser_power_adjust,
+            convergence_time_ms: r.convergence_time_ms,
+            final_ber: r.final_ber,
+            final_eye_margin: r.final_eye_margin,
+            power_savings: r.power_savings,
+            source: source as i32,
+        }
+    }
+}
+
+/// Metadata value as a non-empty string.
+fn metadata<'a, T>(req: &'a Request<T>, key: &str) -> Option<&'a str> {
+    req.metadata().get(key).and_then(|v| v.to_str().ok()).filter(|v| !v.is_empty())
+}
+
+struct GrpcService {
+    service: Arc<CorridorService>,
+}
+
+#[tonic::async_trait]
+impl Corridors for GrpcService {
+    async fn allocate(&self, req: Request<pb::CorridorRequest>) -> Result<Response<pb::Corridor>, Status> {
+        let created_by = metadata(&req, "x-user").map(str::to_string).unwrap_or_else(anonymous_user);
+        let key = metadata(&req, "idempotency-key").map(str::to_string);
+        let corridor_req = CorridorRequest::try_from(req.into_inner()).map_err(status_from)?;
+        let result = match key {
+            Some(key) => self.service.allocate_idempotent(corridor_req, &created_by, &key, &|_| {}).await,
+            None => self.service.allocate_corridor(corridor_req, &created_by).await,
+        };
+        result.map(|c| Response::new(pb::Corridor::from(&c))).map_err(status_from)
+    }
+
+    async fn get(&self, req: Request<pb::GetRequest>) -> Result<Response<pb::Corridor>, Status> {
+        let req = req.into_inner();
+        let found = if req.include_archived {
+            self.service.get_corridor_or_archived(&req.id).await
+        } else {
+            self.service.get_corridor(&req.id).await
+        };
+        found.map(|c| Response::new(pb::Corridor::from(&c))).map_err(status_from)
+    }
+
+    async fn list(&self, req: Request<pb::ListRequest>) -> Result<Response<pb::ListResponse>, Status> {
+        let req = req.into_inner();
+        let query = ListQuery {
+            created_by: req.created_by,
+            fields: None,
+            status: req.status,
+            corridor_type: req.corridor_type,
+            limit: req.limit.map(|l| l as usize),
+            offset: req.offset as usize,
+            labels: req.labels.into_iter().collect(),
+        };
+        let page = self.service.list_corridors(&query).await;
+        Ok(Response::new(pb::ListResponse {
+            items: page.items.iter().map(pb::Corridor::from).collect(),
+            total: page.total as u64,
+            offset: page.offset as u64,
+        }))
+    }
+
+    async fn telemetry(&self, req: Request<pb::TelemetryRequest>) -> Result<Response<pb::TelemetryData>, Status> {
+        let id = req.into_inner().id;
+        self.service.get_telemetry(&id).await.map(|t| Response::new(t.into())).map_err(status_from)
+    }
+
+    async fn recalibrate(
+        &self,
+        req: Request<pb::RecalibrateRequest>,
+    ) -> Result<Response<pb::RecalibrateResponse>, Status> {
+        let req = req.into_inner();
+        let recal = RecalibrateRequest { target_ber: req.target_ber, ambient_profile: req.ambient_profile };
+        self.service.recalibrate(&req.id, recal).await.map(|r| Response::new(r.into())).map_err(status_from)
+    }
+}
+
+/// Serve the `Corridors` gRPC service on `CORRD_GRPC_PORT` (default 50051, 0 turns
+/// it off) of the REST bind address, over the same `CorridorService`. Calls need
+/// `CORRD_API_TOKEN` as `authorization: Bearer` metadata when it is set; they are
+/// not rate limited.
+pub fn spawn(service: Arc<CorridorService>, rest_addr: SocketAddr) -> Result<()> {
+    let port = env::var("CORRD_GRPC_PORT").unwrap_or_else(|_| "50051".to_string());
+    let port: u16 = port.parse().map_err(|e| anyhow::anyhow!("invalid CORRD_GRPC_PORT '{}': {}", port, e))?;
+    if port == 0 {
+        return Ok(());
+    }
+    let addr = SocketAddr::new(rest_addr.ip(), port);
+    let auth = AuthConfig::from_env();
+    // The interceptor signature is fixed by tonic.
+    #[allow(clippy::result_large_err)]
+    let check = move |req: Request<()>| {
+        let header = req.metadata().get("authorization").and_then(|v| v.to_str().ok());
+        if auth.api_authorized(header) {
+            Ok(req)
+        } else {
+            Err(status_from(CorridorError::Unauthorized))
+        }
+    };
+    let server = CorridorsServer::with_interceptor(GrpcService { service }, check);
+    println!("Starting CorridorOS corrd gRPC server on {}", addr);
+    tokio::spawn(async move {
+        let served = tonic::transport::Server::builder()
+            .add_service(server)
+            .serve_with_shutdown(addr, crate::shutdown_signal())
+            .await;
+        if let Err(e) = served {
+            tracing::error!("gRPC server on {} stopped: {}", addr, e);
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::env;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tracing::Instrument;
+
+use crate::error::CorridorError;
+use crate::Corridor;
+
+/// Where a hook is delivered: an HTTP endpoint (`http://...`) or a local command
+/// that receives the JSON payload on stdin.
+#[derive(Debug, Clone)]
+pub enum HookTarget {
+    Http(String),
+    Command(String),
+}
+
+impl HookTarget {
+    fn parse(s: &str) -> Self {
+        if s.starts_with("http://") || s.starts_with("https://") {
+            HookTarget::Http(s.to_string())
+        } else {
+            HookTarget::Command(s.to_string())
+        }
+    }
+
+    /// Deliver `payload` and return the response body (HTTP) or stdout (command).
+    /// Both are bounded by `CORRD_WEBHOOK_TIMEOUT_MS`.
+    async fn call(&self, payload: Vec<u8>) -> Result<Vec<u8>> {
+        let timeout = crate::http::timeout_from_env("CORRD_WEBHOOK_TIMEOUT_MS");
+        match self {
+            HookTarget::Http(url) => {
+                let (url, span) = (url.clone(), tracing::Span::current());
+                tokio::task::spawn_blocking(move || {
+                    let _entered = span.enter();
+                    crate::http::post(&url, payload, timeout)
+                })
+                .await
+                .map_err(|e| anyhow::anyhow!(format!("join error: {}", e)))?
+            }
+            HookTarget::Command(cmd) => run_command(cmd, payload, timeout).await,
+        }
+    }
+}
+
+/// Run `cmd` with `payload` on stdin and return its stdout. Stdin is written while
+/// stdout is drained, so a hook that answers before reading everything can't
+/// deadlock on a full pipe; a hook still running at `timeout` is killed.
+async fn run_command(cmd: &str, payload: Vec<u8>, timeout: Duration) -> Result<Vec<u8>> {
+    let mut child = tokio::process::Command::new(cmd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("spawn {} failed: {}", cmd, e))?;
+    let stdin = child.stdin.take();
+    let feed = async move {
+        if let Some(mut stdin) = stdin {
+            stdin.write_all(&payload).await?;
+        }
+        Ok::<_, std::io::Error>(())
+    };
+    // On timeout the dropped future drops the child, and `kill_on_drop` kills it.
+    let (fed, out) = tokio::time::timeout(timeout, async { tokio::join!(feed, child.wait_with_output()) })
+        .await
+        .map_err(|_| anyhow::anyhow!("{} timed out after {} ms", cmd, timeout.as_millis()))?;
+    let out = out?;
+    if !out.status.success() {
+        return Err(anyhow::anyhow!("{} exited with {}", cmd, out.status));
+    }
+    fed?;
+    Ok(out.stdout)
+}
+
+#[derive(Debug, Deserialize)]
+struct AdmissionDecision {
+    allowed: bool,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// Admission (pre-allocation) and notification (post-allocation) hooks, plus the
+/// inventory webhook that hears about every allocation and deallocation.
+pub struct Hooks {
+    admission: Option<HookTarget>,
+    post_allocate: Option<HookTarget>,
+    fail_open: bool,
+    webhook_url: Option<String>,
+    webhook_attempts: u32,
+    webhook_backoff: Duration,
+    webhook_failures: prometheus::IntCounter,
+}
+
+impl Hooks {
+    /// Read `CORRD_ADMISSION_WEBHOOK`, `CORRD_POST_ALLOCATE_WEBHOOK`,
+    /// `CORRD_ADMISSION_FAIL_OPEN` (default: fail closed) and `CORRD_WEBHOOK_URL`
+    /// with its `CORRD_WEBHOOK_RETRY_ATTEMPTS` (3) and `CORRD_WEBHOOK_RETRY_BACKOFF_MS` (200).
+    pub fn from_env(registry: &prometheus::Registry) -> Result<Self> {
+        Ok(Self {
+            webhook_url: env::var("CORRD_WEBHOOK_URL").ok().filter(|v| !v.is_empty()),
+            webhook_attempts: env::var("CORRD_WEBHOOK_RETRY_ATTEMPTS").ok().and_then(|v| v.parse().ok()).unwrap_or(3u32).max(1),
+            webhook_backoff: Duration::from_millis(
+                env::var("CORRD_WEBHOOK_RETRY_BACKOFF_MS").ok().and_then(|v| v.parse().ok()).unwrap_or(200),
+            ),
+            webhook_failures: crate::register(registry, prometheus::IntCounter::with_opts(crate::metric_opts(
+                "corridor_webhook_failures_total",
+                "Inventory webhook events dropped after all delivery attempts failed"
+            ))?)?,
+            admission: env::var("CORRD_ADMISSION_WEBHOOK").ok().filter(|v| !v.is_empty()).map(|v| HookTarget::parse(&v)),
+            post_allocate: env::var("CORRD_POST_ALLOCATE_WEBHOOK").ok().filter(|v| !v.is_empty()).map(|v| HookTarget::parse(&v)),
+            fail_open: env::var("CORRD_ADMISSION_FAIL_OPEN").map(|v| v == "true" || v == "1").unwrap_or(false),
+        })
+    }
+
+    /// Ask the admission hook about a proposed corridor. `Err` carries the denial reason.
+    pub async fn admit(&self, proposal: &serde_json::Value) -> Result<()> {
+        let Some(target) = self.admission.clone() else { return Ok(()) };
+        let payload = serde_json::to_vec(proposal)?;
+        let outcome = target.call(payload).await.and_then(|body| {
+            serde_json::from_slice::<AdmissionDecision>(&body).map_err(|e| anyhow::anyhow!("invalid admission response: {}", e))
+        });
+        match outcome {
+            Ok(d) if d.allowed => Ok(()),
+            Ok(d) => Err(anyhow::anyhow!(
+                "admission denied: {}",
+                d.reason.unwrap_or_else(|| "no reason given".to_string())
+            )),
+            Err(e) if self.fail_open => {
+                tracing::warn!("admission webhook failed, allowing (fail open): {}", e);
+                Ok(())
+            }
+            Err(e) => Err(CorridorError::Downstream(format!("admission webhook failed: {}", e)).into()),
+        }
+    }
+
+    /// POST `{"type": kind, "corridor": ..., "ts": ...}` to `CORRD_WEBHOOK_URL` in the
+    /// background, retrying with doubling backoff. Never blocks or fails the caller;
+    /// events still undelivered after the last attempt are logged and counted.
+    pub fn publish(&self, kind: &str, corridor: &Corridor, ts: chrono::DateTime<chrono::Utc>) {
+        let Some(url) = self.webhook_url.clone() else { return };
+        let event = serde_json::json!({"type": kind, "corridor": corridor, "ts": ts});
+        let Ok(payload) = serde_json::to_vec(&event) else { return };
+        let (attempts, mut backoff, failures) = (self.webhook_attempts, self.webhook_backoff, self.webhook_failures.clone());
+        let timeout = crate::http::timeout_from_env("CORRD_WEBHOOK_TIMEOUT_MS");
+        let (kind, id) = (kind.to_string(), corridor.id.clone());
+        let span = tracing::Span::current();
+        tokio::spawn(async move {
+            for attempt in 1..=attempts {
+                let (url, payload, span) = (url.clone(), payload.clone(), span.clone());
+                let sent = tokio::task::spawn_blocking(move || {
+                    let _entered = span.enter();
+                    crate::http::post(&url, payload, timeout)
+                })
+                .await
+                .map_err(|e| anyhow::anyhow!("join error: {}", e))
+                .and_then(|r| r);
+                match sent {
+                    Ok(_) => return,
+                    Err(e) if attempt < attempts => {
+                        tracing::warn!("{} webhook for {} attempt {}/{} failed, retrying in {:?}: {}", kind, id, attempt, attempts, backoff, e);
+                        tokio::time::sleep(backoff).await;
+                        backoff = backoff.saturating_mul(2).min(crate::MAX_RETRY_BACKOFF);
+                    }
+                    Err(e) => {
+                        tracing::error!("{} webhook for {} dropped after {} attempts: {}", kind, id, attempts, e);
+                        failures.inc();
+                    }
+                }
+            }
+        });
+    }
+
+    /// Fire-and-forget notification after a successful allocation.
+    pub fn notify_allocated(&self, event: &serde_json::Value) {
+        let Some(target) = self.post_allocate.clone() else { return };
+        let Ok(payload) = serde_json::to_vec(event) else { return };
+        tokio::spawn(
+            async move {
+                if let Err(e) = target.call(payload).await {
+                    tracing::warn!("post-allocation hook failed: {}", e);
+                }
+            }
+            .instrument(tracing::Span::current()),
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use anyhow::Result;
+use reqwest::blocking::Client;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::tls::TlsPolicy;
+
+/// Shared blocking client for HELIOPASS, attestd and webhook calls. Built on first
+/// use with the `CORRD_TLS_*` policy so `https://` endpoints get the same minimum
+/// version and cipher suites as the listener. Connections are pooled per host and
+/// kept alive between calls, up to `CORRD_HTTP_POOL_MAX_IDLE` (default 8) idle
+/// per host. Call only from blocking contexts.
+fn client() -> Result<&'static Client> {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    if let Some(c) = CLIENT.get() {
+        return Ok(c);
+    }
+    let built = build_client(crate::tls::client_config(&TlsPolicy::from_env()?, None, None)?)?;
+    Ok(CLIENT.get_or_init(|| built))
+}
+
+/// A pooled blocking client over `tls`, for callers that need their own roots or
+/// client certificate. Build and drop it only from blocking contexts.
+pub fn build_client(tls: rustls::ClientConfig) -> Result<Client> {
+    let max_idle = std::env::var("CORRD_HTTP_POOL_MAX_IDLE").ok().and_then(|v| v.parse().ok()).unwrap_or(8);
+    let built = Client::builder()
+        .use_preconfigured_tls(tls)
+        .pool_max_idle_per_host(max_idle)
+        .pool_idle_timeout(Duration::from_secs(90))
+        .tcp_keepalive(Duration::from_secs(30))
+        .build()?;
+    Ok(built)
+}
+
+/// `corridor_downstream_request_duration_seconds`, by downstream host and method.
+/// Not registered anywhere; each service registers it with its own registry.
+pub fn request_duration() -> &'static prometheus::HistogramVec {
+    static HISTOGRAM: OnceLock<prometheus::HistogramVec> = OnceLock::new();
+    HISTOGRAM.get_or_init(|| {
+        prometheus::HistogramVec::new(
+            prometheus::HistogramOpts::from(crate::metric_opts(
+                "corridor_downstream_request_duration_seconds",
+                "Duration of HTTP calls to HELIOPASS, attestd and webhooks, connect included (s)",
+            )),
+            &["downstream", "method"],
+        )
+        .expect("valid histogram options")
+    })
+}
+
+fn observe(method: &str, url: &str, started: Instant) {
+    let downstream = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| Some(format!("{}:{}", u.host_str()?, u.port_or_known_default()?)))
+        .unwrap_or_else(|| "unknown".to_string());
+    request_duration().with_label_values(&[&downstream, method]).observe(started.elapsed().as_secs_f64());
+}
+
+/// Timeout in milliseconds from `key`, defaulting to 2000.
+pub fn timeout_from_env(key: &str) -> Duration {
+    Duration::from_millis(std::env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(2000))
+}
+
+/// Describe a transport failure, keeping the `reqwest::Error` as the source so
+/// callers can tell connect/handshake failures apart.
+fn send_error(method: &str, url: &str, timeout: Duration, e: reqwest::Error) -> anyhow::Error {
+    let message = if e.is_timeout() {
+        format!("{} {} timed out after {} ms", method, url, timeout.as_millis())
+    } else {
+        format!("{} {} failed: {}", method, url, e)
+    };
+    anyhow::Error::new(e).context(message)
+}
+
+/// Whether `err` came from failing to connect or to complete the exchange, TLS
+/// handshake included. Under TLS 1.3 a rejected client certificate only surfaces
+/// once the request is sent, so that counts too; timeouts don't.
+pub fn is_transport_error(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|c| c.downcast_ref::<reqwest::Error>())
+        .any(|e| !e.is_timeout() && (e.is_connect() || e.is_request()))
+}
+
+/// POST a raw JSON body and return the response body; non-2xx statuses are errors.
+/// `timeout` bounds the whole exchange, connect included.
+pub fn post(url: &str, body: Vec<u8>, timeout: Duration) -> Result<Vec<u8>> {
+    let started = Instant::now();
+    let result = send_post(url, body, timeout);
+    observe("POST", url, started);
+    result
+}
+
+fn send_post(url: &str, body: Vec<u8>, timeout: Duration) -> Result<Vec<u8>> {
+    let resp = client()?
+        .post(url)
+        .timeout(timeout)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .map_err(|e| send_error("POST", url, timeout, e))?;
+    let status = resp.status();
+    tracing::info!("POST {} returned {}", url, status);
+    if !status.is_success() {
+        return Err(anyhow::anyhow!("POST {} returned {}", url, status));
+    }
+    Ok(resp.bytes().map_err(|e| send_error("POST", url, timeout, e))?.to_vec())
+}
+
+pub fn post_json<B: Serialize, T: DeserializeOwned>(url: &str, body: &B, timeout: Duration) -> Result<T> {
+    let bytes = post(url, serde_json::to_vec(body)?, timeout)?;
+    serde_json::from_slice(&bytes).map_err(|e| anyhow::anyhow!("invalid JSON from {}: {}", url, e))
+}
+
+pub fn get_json<T: DeserializeOwned>(url: &str, timeout: Duration) -> Result<T> {
+    get_json_with(client()?, url, timeout)
+}
+
+/// `get_json` over a caller-supplied client.
+pub fn get_json_with<T: DeserializeOwned>(client: &Client, url: &str, timeout: Duration) -> Result<T> {
+    let started = Instant::now();
+    let result = send_get(client, url, timeout);
+    observe("GET", url, started);
+    serde_json::from_slice(&result?).map_err(|e| anyhow::anyhow!("invalid JSON from {}: {}", url, e))
+}
+
+fn send_get(client: &Client, url: &str, timeout: Duration) -> Result<Vec<u8>> {
+    let resp = client.get(url).timeout(timeout).send().map_err(|e| send_error("GET", url, timeout, e))?;
+    let status = resp.status();
+    tracing::info!("GET {} returned {}", url, status);
+    if !status.is_success() {
+        return Err(anyhow::anyhow!("GET {} returned {}", url, status));
+    }
+    Ok(resp.bytes().map_err(|e| send_error("GET", url, timeout, e))?.to_vec())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    Http,
+    Https,
+}
+
+/// A configured downstream such as `HELIOPASS_URL`: where to connect, plus a
+/// base path (empty, or `/prefix` without a trailing slash) that request paths
+/// are joined under. `host` is a name or a bare IP, IPv6 without brackets, so
+/// it can be passed straight to a connect call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseUrl {
+    pub scheme: Scheme,
+    pub host: String,
+    pub port: u16,
+    pub base_path: String,
+}
+
+impl BaseUrl {
+    /// Absolute URL for `path` under the base path.
+    pub fn join(&self, path: &str) -> String {
+        format!("{}/{}", self, path.trim_start_matches('/'))
+    }
+}
+
+/// `scheme://host:port/base/path`, ready for `/`-prefixed paths to be appended.
+impl std::fmt::Display for BaseUrl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scheme = match self.scheme {
+            Scheme::Http => "http",
+            Scheme::Https => "https",
+        };
+        if self.host.contains(':') {
+            write!(f, "{}://[{}]:{}{}", scheme, self.host, self.port, self.base_path)
+        } else {
+            write!(f, "{}://{}:{}{}", scheme, self.host, self.port, self.base_path)
+        }
+    }
+}
+
+/// Parse an `http(s)://host[:port][/base/path]` URL. The port defaults by scheme;
+/// credentials, queries and fragments are refused since they'd be silently dropped.
+pub fn parse_base_url(s: &str) -> Result<BaseUrl> {
+    let parsed = reqwest::Url::parse(s.trim()).map_err(|e| anyhow::anyhow!("invalid URL {}: {}", s, e))?;
+    let scheme = match parsed.scheme() {
+        "http" => Scheme::Http,
+        "https" => Scheme::Https,
+        other => return Err(anyhow::anyhow!("URL {} must be http or https, got {}", s, other)),
+    };
+    if !parsed.username().is_empty() || parsed.password().is_some() {
+        return Err(anyhow::anyhow!("URL {} must not carry credentials", s));
+    }
+    if parsed.query().is_some() || parsed.fragment().is_some() {
+        return Err(anyhow::anyhow!("URL {} must not have a query or fragment", s));
+    }
+    // IPv6 literals come back bracketed; keep the bare address.
+    let host = parsed.host_str().ok_or_else(|| anyhow::anyhow!("URL {} has no host", s))?;
+    let host = host.strip_prefix('[').and_then(|h| h.strip_suffix(']')).unwrap_or(host).to_string();
+    let port = parsed.port_or_known_default().ok_or_else(|| anyhow::anyhow!("URL {} has no port", s))?;
+    let base_path = parsed.path().trim_end_matches('/').to_string();
+    Ok(BaseUrl { scheme, host, port, base_path })
+}
+
+/// Whether a TCP connection to the host behind `url` opens within `timeout`.
+pub async fn probe(url: &str, timeout: Duration) -> Result<()> {
+    let BaseUrl { host, port, .. } = parse_base_url(url)?;
+    let connect = tokio::net::TcpStream::connect((host.as_str(), port));
+    tokio::time::timeout(timeout, connect)
+        .await
+        .map_err(|_| anyhow::anyhow!("connect to {}:{} timed out after {} ms", host, port, timeout.as_millis()))??;
+    Ok(())
+}
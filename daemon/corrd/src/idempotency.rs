@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+enum Entry {
+    /// An allocation with this key is running.
+    Pending,
+    /// The corridor the key created.
+    Done(String),
+}
+
+/// What `IdempotencyCache::claim` found for a key.
+pub enum Claim<'a> {
+    /// First use; the caller allocates and then calls `ClaimGuard::complete`.
+    New(ClaimGuard<'a>),
+    /// Already used to create this corridor.
+    Replay(String),
+    /// Another request with the key hasn't finished yet.
+    InFlight,
+}
+
+/// `Idempotency-Key` values seen on `POST /v1/corridors`, scoped by caller.
+pub struct IdempotencyCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<(String, String), (Entry, Instant)>>,
+}
+
+impl IdempotencyCache {
+    /// `CORRD_IDEMPOTENCY_TTL_MS` (default 24 hours).
+    pub fn from_env() -> Self {
+        let ttl = env::var("CORRD_IDEMPOTENCY_TTL_MS").ok().and_then(|v| v.parse().ok()).unwrap_or(86_400_000);
+        Self { ttl: Duration::from_millis(ttl), entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// Look up `key` for `user`, reserving it when it's unused or expired.
+    pub fn claim(&self, user: &str, key: &str) -> Claim<'_> {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (_, expires_at)| now < *expires_at);
+        match entries.get(&(user.to_string(), key.to_string())) {
+            Some((Entry::Done(id), _)) => Claim::Replay(id.clone()),
+            Some((Entry::Pending, _)) => Claim::InFlight,
+            None => {
+                entries.insert((user.to_string(), key.to_string()), (Entry::Pending, now + self.ttl));
+                Claim::New(ClaimGuard { cache: self, user: user.to_string(), key: key.to_string(), completed: false })
+            }
+        }
+    }
+
+}
+
+/// A reserved key. Dropping it without `complete` (the allocation failed, or the
+/// request was abandoned mid-flight) frees the key so a retry can run.
+pub struct ClaimGuard<'a> {
+    cache: &'a IdempotencyCache,
+    user: String,
+    key: String,
+    completed: bool,
+}
+
+impl ClaimGuard<'_> {
+    /// Record the corridor the key created; replays are served for the TTL from now.
+    pub fn complete(mut self, corridor_id: &str) {
+        let mut entries = self.cache.entries.lock().unwrap();
+        entries.insert(
+            (std::mem::take(&mut self.user), std::mem::take(&mut self.key)),
+            (Entry::Done(corridor_id.to_string()), Instant::now() + self.cache.ttl),
+        );
+        self.completed = true;
+    }
+}
+
+impl Drop for ClaimGuard<'_> {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.cache.entries.lock().unwrap().remove(&(std::mem::take(&mut self.user), std::mem::take(&mut self.key)));
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use prometheus::HistogramVec;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Downstream services whose call latency is tracked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dependency {
+    Heliopass,
+    Attestd,
+}
+
+impl Dependency {
+    pub fn name(self) -> &'static str {
+        match self {
+            Dependency::Heliopass => "heliopass",
+            Dependency::Attestd => "attestd",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct LatencySummary {
+    pub samples: usize,
+    pub p50_ms: Option<f64>,
+    pub p95_ms: Option<f64>,
+    pub degraded: bool,
+}
+
+/// Rolling window of recent call latencies per dependency, mirrored into a histogram.
+pub struct LatencyTracker {
+    window: usize,
+    degraded_p95: Duration,
+    heliopass: Mutex<VecDeque<Duration>>,
+    attestd: Mutex<VecDeque<Duration>>,
+    histogram: HistogramVec,
+}
+
+impl LatencyTracker {
+    pub fn new(window: usize, degraded_p95: Duration, histogram: HistogramVec) -> Self {
+        Self {
+            window: window.max(1),
+            degraded_p95,
+            heliopass: Mutex::new(VecDeque::new()),
+            attestd: Mutex::new(VecDeque::new()),
+            histogram,
+        }
+    }
+
+    fn samples(&self, dep: Dependency) -> &Mutex<VecDeque<Duration>> {
+        match dep {
+            Dependency::Heliopass => &self.heliopass,
+            Dependency::Attestd => &self.attestd,
+        }
+    }
+
+    pub fn record(&self, dep: Dependency, elapsed: Duration) {
+        self.histogram.with_label_values(&[dep.name()]).observe(elapsed.as_secs_f64());
+        let mut samples = self.samples(dep).lock().unwrap();
+        if samples.len() == self.window {
+            samples.pop_front();
+        }
+        samples.push_back(elapsed);
+    }
+
+    pub fn summary(&self, dep: Dependency) -> LatencySummary {
+        let mut sorted: Vec<Duration> = self.samples(dep).lock().unwrap().iter().copied().collect();
+        sorted.sort();
+        let pct = |p: f64| -> Option<Duration> {
+            if sorted.is_empty() {
+                return None;
+            }
+            let idx = ((sorted.len() as f64 * p).ceil() as usize).clamp(1, sorted.len()) - 1;
+            Some(sorted[idx])
+        };
+        let p95 = pct(0.95);
+        LatencySummary {
+            samples: sorted.len(),
+            p50_ms: pct(0.50).map(|d| d.as_secs_f64() * 1000.0),
+            p95_ms: p95.map(|d| d.as_secs_f64() * 1000.0),
+            degraded: p95.map(|d| d > self.degraded_p95).unwrap_or(false),
+        }
+    }
+}
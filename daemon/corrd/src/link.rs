@@ -0,0 +1,187 @@
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Optimization goal used when choosing a lane configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OptimizeFor {
+    #[default]
+    Throughput,
+    Power,
+    Balanced,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum Modulation {
+    #[serde(rename = "NRZ")]
+    Nrz,
+    #[serde(rename = "PAM4")]
+    Pam4,
+}
+
+/// One selectable per-lane operating point.
+#[derive(Debug, Clone, Copy)]
+struct LaneOption {
+    modulation: Modulation,
+    lane_gbps: u32,
+    pj_per_bit: f64,
+    /// Pre-FEC bit error rate at this operating point.
+    raw_ber: f64,
+}
+
+/// Supported per-lane operating points, slowest (most efficient) first.
+const LANE_OPTIONS: [LaneOption; 4] = [
+    LaneOption { modulation: Modulation::Nrz, lane_gbps: 25, pj_per_bit: 0.7, raw_ber: 1.0e-14 },
+    LaneOption { modulation: Modulation::Nrz, lane_gbps: 50, pj_per_bit: 0.85, raw_ber: 1.0e-12 },
+    LaneOption { modulation: Modulation::Pam4, lane_gbps: 100, pj_per_bit: 1.1, raw_ber: 1.0e-7 },
+    LaneOption { modulation: Modulation::Pam4, lane_gbps: 200, pj_per_bit: 1.4, raw_ber: 1.0e-6 },
+];
+
+/// Modulation formats and per-lane rates the link model can choose from.
+pub fn operating_points() -> Vec<(Modulation, u32)> {
+    LANE_OPTIONS.iter().map(|o| (o.modulation, o.lane_gbps)).collect()
+}
+
+/// Minimum capacity headroom over `min_gbps` a `balanced` plan tries to keep.
+const BALANCED_HEADROOM: f64 = 0.25;
+/// Share of line rate spent on FEC parity (RS(544,514)).
+const FEC_OVERHEAD: f64 = 0.055;
+/// Extra encode/decode energy when FEC is enabled.
+const FEC_PJ_PER_BIT: f64 = 0.1;
+/// Best post-FEC BER the model will ever report.
+const BER_FLOOR: f64 = 1.0e-18;
+
+/// Reach over which channel impairments cost one decade of BER.
+const REACH_DECADE_MM: f64 = 300.0;
+/// Per-lane load above which BER degrades quadratically.
+const LANE_LOAD_REF_GBPS: f64 = 50.0;
+/// Wavelength spread that doubles the BER (dispersion and gain tilt across the plan).
+const SPREAD_REF_NM: f64 = 20.0;
+
+/// Multiplicative BER penalty (>= 1) from reach, per-lane load and wavelength spread.
+pub fn channel_penalty(reach_mm: u32, lanes: u32, min_gbps: u32, lambda_nm: &[u32]) -> f64 {
+    let reach = 10f64.powf(reach_mm as f64 / REACH_DECADE_MM);
+    let per_lane = min_gbps as f64 / lanes.max(1) as f64;
+    let load = (per_lane / LANE_LOAD_REF_GBPS).max(1.0).powi(2);
+    let spread_nm = match (lambda_nm.iter().min(), lambda_nm.iter().max()) {
+        (Some(lo), Some(hi)) => (hi - lo) as f64,
+        _ => 0.0,
+    };
+    reach * load * (1.0 + spread_nm / SPREAD_REF_NM)
+}
+
+/// Q-factor at the pre-FEC threshold (BER 1e-3); the eye is closed at or below it.
+const Q_CLOSED: f64 = 3.09;
+/// Eye margin below which a corridor is reported as `marginal`.
+pub const EYE_MARGIN_OK: f64 = 0.3;
+
+/// Q-factor for a BER under Gaussian noise, inverting BER ≈ exp(-Q²/2) / (Q·√(2π)).
+pub fn q_factor(ber: f64) -> f64 {
+    let ber = ber.clamp(1.0e-300, 0.5);
+    let mut q: f64 = 1.0;
+    for _ in 0..8 {
+        q = (2.0 * (1.0 / (ber * q.max(1.0) * (2.0 * std::f64::consts::PI).sqrt())).ln()).max(0.0).sqrt();
+    }
+    q
+}
+
+/// Normalized eye opening in [0, 1], the scale HELIOPASS uses for `final_eye_margin`:
+/// the share of Q above the closed-eye threshold, scaled down when the plan falls
+/// short of the requested rate.
+pub fn eye_margin(ber: f64, achievable_gbps: u32, min_gbps: u32) -> f64 {
+    let q = q_factor(ber);
+    let opening = if q > Q_CLOSED { 1.0 - Q_CLOSED / q } else { 0.0 };
+    let rate = if min_gbps == 0 { 1.0 } else { (achievable_gbps as f64 / min_gbps as f64).min(1.0) };
+    opening * rate
+}
+
+/// `ok` or `marginal` for a numeric eye margin.
+pub fn eye_margin_category(margin: f64) -> &'static str {
+    if margin >= EYE_MARGIN_OK { "ok" } else { "marginal" }
+}
+
+/// Lane configuration chosen by the link model.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LinkPlan {
+    pub modulation: Modulation,
+    pub lane_rate_gbps: u32,
+    pub fec: bool,
+    pub capacity_gbps: u32,
+    pub est_power_pj_per_bit: f64,
+    pub est_ber: f64,
+}
+
+impl LinkPlan {
+    fn new(opt: LaneOption, fec: bool, lanes: u32) -> Self {
+        let line_gbps = opt.lane_gbps.saturating_mul(lanes) as f64;
+        let (capacity, pj, ber) = if fec {
+            // Hard-decision RS FEC roughly squares the pre-FEC error rate.
+            (line_gbps * (1.0 - FEC_OVERHEAD), opt.pj_per_bit + FEC_PJ_PER_BIT, (opt.raw_ber * opt.raw_ber).max(BER_FLOOR))
+        } else {
+            (line_gbps, opt.pj_per_bit, opt.raw_ber)
+        };
+        LinkPlan {
+            modulation: opt.modulation,
+            lane_rate_gbps: opt.lane_gbps,
+            fec,
+            capacity_gbps: capacity as u32,
+            est_power_pj_per_bit: pj,
+            est_ber: ber,
+        }
+    }
+}
+
+/// Pick a lane rate, modulation and FEC mode for `lanes` lanes carrying `min_gbps`.
+///
+/// Without `max_ber`, PAM4 always runs with FEC and NRZ without. `throughput` takes
+/// the fastest plan, `power` the lowest pJ/bit one that still meets `min_gbps`, and
+/// `balanced` the lowest pJ/bit one leaving `BALANCED_HEADROOM` spare capacity.
+/// With `max_ber`, only plans meeting the ceiling are considered, which can turn FEC
+/// on or step down the lane rate; if none qualifies the best achievable BER is reported.
+/// When nothing meets `min_gbps` the fastest plan is returned and the caller sees
+/// capacity below the floor.
+pub fn plan_link(lanes: u32, min_gbps: u32, optimize: OptimizeFor, max_ber: Option<f64>) -> Result<LinkPlan> {
+    let lanes = lanes.max(1);
+    let mut candidates: Vec<LinkPlan> = Vec::new();
+    for opt in LANE_OPTIONS {
+        match (opt.modulation, max_ber) {
+            (Modulation::Pam4, _) => candidates.push(LinkPlan::new(opt, true, lanes)),
+            (Modulation::Nrz, None) => candidates.push(LinkPlan::new(opt, false, lanes)),
+            (Modulation::Nrz, Some(_)) => {
+                candidates.push(LinkPlan::new(opt, false, lanes));
+                candidates.push(LinkPlan::new(opt, true, lanes));
+            }
+        }
+    }
+    if let Some(ceiling) = max_ber {
+        let best = candidates.iter().map(|p| p.est_ber).fold(f64::INFINITY, f64::min);
+        candidates.retain(|p| p.est_ber <= ceiling);
+        if candidates.is_empty() {
+            return Err(anyhow::anyhow!(
+                "max_ber {:e} is not achievable for {} lanes; best achievable BER is {:e}",
+                ceiling, lanes, best
+            ));
+        }
+    }
+
+    let fastest = *candidates
+        .iter()
+        .max_by(|a, b| a.capacity_gbps.cmp(&b.capacity_gbps).then(b.est_power_pj_per_bit.total_cmp(&a.est_power_pj_per_bit)))
+        .expect("at least one candidate");
+    let cheapest_meeting = |floor: f64| {
+        candidates
+            .iter()
+            .filter(|p| p.capacity_gbps as f64 >= floor)
+            .min_by(|a, b| a.est_power_pj_per_bit.total_cmp(&b.est_power_pj_per_bit))
+            .copied()
+    };
+    let chosen = match optimize {
+        OptimizeFor::Throughput => fastest,
+        OptimizeFor::Power => cheapest_meeting(min_gbps as f64).unwrap_or(fastest),
+        OptimizeFor::Balanced => cheapest_meeting(min_gbps as f64 * (1.0 + BALANCED_HEADROOM))
+            .or_else(|| cheapest_meeting(min_gbps as f64))
+            .unwrap_or(fastest),
+    };
+    Ok(chosen)
+}
@@ -1,47 +1,151 @@
+// The warp route chain in `main` nests deeper than the default limit allows.
+#![recursion_limit = "256"]
+
+mod alerts;
+mod error;
+mod attestation;
+mod auth;
+mod clock;
+mod compress;
+mod config;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod hooks;
+mod http;
+mod idempotency;
+mod latency;
+mod link;
+mod openapi;
+mod persist;
+mod ratelimit;
+mod receipt;
+mod rejection;
+mod tls;
+mod version;
+mod wavelength;
+
 use anyhow::Result;
+use alerts::{Alert, AlertEngine, AlertThresholds};
+use attestation::{AttestationCache, AttestationVerifier, AttestdVerifier, Verdict};
+use clock::{Clock, SystemClock};
+use error::CorridorError;
+use hooks::Hooks;
+use idempotency::{Claim, IdempotencyCache};
+use latency::{Dependency, LatencyTracker};
+use link::{plan_link, Modulation, OptimizeFor};
+use persist::StateStore;
+use receipt::ReceiptSigner;
+use wavelength::{Band, CapacityReport, LambdaAllocator};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use std::env;
-use std::io::{Read, Write};
-use std::net::TcpStream;
+use futures_util::{SinkExt, StreamExt};
 use warp::Filter;
-use prometheus::{Encoder, GaugeVec, TextEncoder};
+use prometheus::{CounterVec, Encoder, GaugeVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, TextEncoder};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CorridorRequest {
     pub corridor_type: CorridorType,
     pub lanes: u32,
     pub lambda_nm: Vec<u32>,
+    /// Wavelengths multiplexed onto each lane; `lambda_nm` then lists lane 1's
+    /// wavelengths first, then lane 2's, and so on. Absent means one per lane.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lambdas_per_lane: Option<u32>,
     pub min_gbps: u32,
     pub latency_budget_ns: u32,
     pub reach_mm: u32,
+    /// Free-form; `dwdm` (any case) also requires every `lambda_nm` on the ITU 100 GHz grid.
     pub mode: String,
     pub qos: QoSSettings,
     pub attestation_required: bool,
     #[serde(default)]
     pub attestation_ticket: Option<String>,
+    #[serde(default)]
+    pub optimize: OptimizeFor,
+    /// Worst acceptable BER; the link model adds FEC or lowers the lane rate to meet it.
+    #[serde(default)]
+    pub max_ber: Option<f64>,
+    /// Physical medium the wavelengths travel on; corridors sharing one can't
+    /// hold the same `lambda_nm`. Absent means no fleet-wide reservation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub medium_id: Option<String>,
+    /// Free-form grouping (tenant, job, environment); see `validate_labels` for limits.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub labels: HashMap<String, String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Serialized as `si_corridor`/`carbon_corridor` (receipts sign that form); `si`,
+/// `carbon` and the older `SiCorridor`/`CarbonCorridor` are accepted on input too.
+///
+/// The wire names of this enum, `CorridorStatus` and `LaneState` are pinned by
+/// explicit renames and `as_str`, so renaming a variant can't change what clients,
+/// receipts and dashboards see.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
 pub enum CorridorType {
+    #[serde(rename = "si_corridor")]
     SiCorridor,
+    #[serde(rename = "carbon_corridor")]
     CarbonCorridor,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl CorridorType {
+    /// The wire name, as serialized.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CorridorType::SiCorridor => "si_corridor",
+            CorridorType::CarbonCorridor => "carbon_corridor",
+        }
+    }
+}
+
+impl std::str::FromStr for CorridorType {
+    type Err = CorridorError;
+
+    /// Keep in step with `canonical_corridor_type` in the Rust SDK.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "si" | "SiCorridor" | "si_corridor" => Ok(CorridorType::SiCorridor),
+            "carbon" | "CarbonCorridor" | "carbon_corridor" => Ok(CorridorType::CarbonCorridor),
+            other => Err(CorridorError::InvalidRequest(format!("unknown corridor_type: {}, expected si|carbon", other))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CorridorType {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        String::deserialize(d)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Scheduling class for a corridor. Anything outside this set fails to
+/// deserialize, and the 400 names the accepted values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+    Critical,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct QoSSettings {
     pub pfc: bool,
-    pub priority: String,
+    pub priority: Priority,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Corridor {
     pub id: String,
     pub corridor_type: CorridorType,
     pub lanes: u32,
     pub lambda_nm: Vec<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lambdas_per_lane: Option<u32>,
     pub min_gbps: u32,
     pub latency_budget_ns: u32,
     pub reach_mm: u32,
@@ -50,22 +154,509 @@ pub struct Corridor {
     pub attestation_required: bool,
     #[serde(default)]
     pub attestation_ticket: Option<String>,
+    /// When attestd says the ticket stops being valid; the corridor moves to
+    /// `AttestationExpired` once it passes unless a re-check finds it renewed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub optimize: OptimizeFor,
+    #[serde(default)]
+    pub max_ber: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub medium_id: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub labels: HashMap<String, String>,
+    pub modulation: Modulation,
+    pub lane_rate_gbps: u32,
+    pub fec: bool,
+    pub est_power_pj_per_bit: f64,
+    /// `est_power_pj_per_bit` × `achievable_gbps`, counted against `CORRD_POWER_BUDGET_MW`.
+    #[serde(default)]
+    pub total_power_mw: f64,
     pub achievable_gbps: u32,
+    /// Headroom over `min_gbps` applied before the lane-rate ceiling (0.04 is 4%).
+    #[serde(default = "legacy_rate_margin")]
+    pub rate_margin: f64,
     pub ber: f64,
+    /// `ok` or `marginal`, derived from `eye_margin_value` (`ok` at 0.3 and above).
+    /// Kept for existing clients; threshold on `eye_margin_value` instead.
     pub eye_margin: String,
+    /// Normalized eye opening in [0, 1], on the same scale as HELIOPASS's
+    /// `final_eye_margin`.
+    #[serde(default)]
+    pub eye_margin_value: f64,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub status: CorridorStatus,
+    /// When the corridor entered its current status.
+    pub status_since: chrono::DateTime<chrono::Utc>,
+    /// Set by `POST /v1/corridors/{id}/ack` while in `Error`; quiets repeat alerts.
+    #[serde(default)]
+    pub acked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ack: Option<Acknowledgement>,
+    /// Pre-created from `CORRD_BOOTSTRAP_CORRIDORS`; `DELETE` is refused while set.
+    #[serde(default)]
+    pub bootstrap: bool,
+    /// The bootstrap entry this corridor was created from, so a restart with restored
+    /// state doesn't allocate it again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bootstrap_id: Option<String>,
+    /// Identity that allocated the corridor (`X-User` header, `anonymous` if absent).
+    #[serde(default = "anonymous_user")]
+    pub created_by: String,
+    /// Hex ed25519 signature over the allocation parameters; verify with `/v1/pubkey`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<String>,
+    /// End of the most recent `recalibrate`, whichever source produced the result.
+    #[serde(default)]
+    pub last_recalibrated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Set by `POST /v1/corridors/{id}/fault`; telemetry honours it until `until`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fault: Option<InjectedFault>,
+    /// Health of each lane; index 0 is lane 1.
+    #[serde(default)]
+    pub lane_status: Vec<LaneState>,
+    /// `achievable_gbps` with every lane up. `achievable_gbps` drops in proportion
+    /// to the lanes that are `Down`.
+    #[serde(default)]
+    pub planned_gbps: u32,
+}
+
+impl Corridor {
+    /// Power draw at the achievable rate; 1 pJ/bit at 1 Gb/s is 1 mW.
+    pub fn power_mw(&self) -> f64 {
+        self.est_power_pj_per_bit * self.achievable_gbps as f64
+    }
+
+    /// 1-based lane carrying the `i`th entry of `lambda_nm`.
+    pub fn lane_of(&self, i: usize) -> usize {
+        i / self.lambdas_per_lane.unwrap_or(1).max(1) as usize + 1
+    }
+
+    /// Lanes still carrying traffic (`Up` or `Degraded`).
+    pub fn lanes_up(&self) -> u32 {
+        self.lane_status.iter().filter(|s| **s != LaneState::Down).count() as u32
+    }
+
+    /// State of the 1-based `lane`; lanes missing from `lane_status` count as `Up`.
+    pub fn lane_state(&self, lane: usize) -> LaneState {
+        lane.checked_sub(1).and_then(|i| self.lane_status.get(i)).copied().unwrap_or_default()
+    }
+
+    /// Seconds since allocation, as of `now`.
+    pub fn uptime_seconds(&self, now: chrono::DateTime<chrono::Utc>) -> i64 {
+        (now - self.created_at).num_seconds().max(0)
+    }
+
+    /// The request parameters this corridor was allocated (or last updated) with.
+    pub fn request(&self) -> CorridorRequest {
+        CorridorRequest {
+            corridor_type: self.corridor_type.clone(),
+            lanes: self.lanes,
+            lambda_nm: self.lambda_nm.clone(),
+            lambdas_per_lane: self.lambdas_per_lane,
+            min_gbps: self.min_gbps,
+            latency_budget_ns: self.latency_budget_ns,
+            reach_mm: self.reach_mm,
+            mode: self.mode.clone(),
+            qos: self.qos.clone(),
+            attestation_required: self.attestation_required,
+            attestation_ticket: self.attestation_ticket.clone(),
+            optimize: self.optimize,
+            max_ber: self.max_ber,
+            medium_id: self.medium_id.clone(),
+            labels: self.labels.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Acknowledgement {
+    pub by: String,
+    pub at: chrono::DateTime<chrono::Utc>,
+    #[serde(default)]
+    pub note: String,
+}
+
+/// Body of `POST /v1/corridors/{id}/fault`. Telemetry reports the given values
+/// instead of the modelled ones for `duration_ms`; omitted values stay modelled.
+/// With `lane`, that lane is set to `lane_state` (default `Down`) until another
+/// fault changes it; `duration_ms` is then only needed alongside telemetry values.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct FaultRequest {
+    #[serde(default)]
+    pub ber: Option<f64>,
+    #[serde(default)]
+    pub temp_c: Option<f64>,
+    #[serde(default)]
+    pub error_count: Option<u64>,
+    #[serde(default)]
+    pub duration_ms: u64,
+    /// 1-based lane to change.
+    #[serde(default)]
+    pub lane: Option<usize>,
+    #[serde(default)]
+    pub lane_state: Option<LaneState>,
+}
+
+impl FaultRequest {
+    fn overrides_telemetry(&self) -> bool {
+        self.ber.is_some() || self.temp_c.is_some() || self.error_count.is_some()
+    }
+}
+
+const DEFAULT_BENCHMARK_STEPS: u32 = 10;
+const MAX_BENCHMARK_STEPS: u32 = 50;
+const DEFAULT_BENCHMARK_STEP_MS: u64 = 100;
+const MAX_BENCHMARK_STEP_MS: u64 = 1_000;
+
+/// Body of `POST /v1/corridors/{id}/benchmark`. Offered load ramps in `steps`
+/// equal increments up to `min_gbps`, holding each for `step_ms`.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct BenchmarkRequest {
+    /// 1-50, default 10.
+    #[serde(default)]
+    pub steps: Option<u32>,
+    /// 0-1000, default 100.
+    #[serde(default)]
+    pub step_ms: Option<u64>,
+}
+
+/// One load step of a benchmark.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BenchmarkStep {
+    pub offered_gbps: u32,
+    /// Offered load capped at `achievable_gbps`.
+    pub sustained_gbps: u32,
+    pub ber: f64,
+    pub eye_margin: f64,
+}
+
+/// Outcome of `POST /v1/corridors/{id}/benchmark`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BenchmarkReport {
+    pub corridor_id: String,
+    /// Highest carried rate at a step whose eye margin stayed at or above 0.3.
+    pub sustained_gbps: u32,
+    pub initial_ber: f64,
+    pub peak_ber: f64,
+    /// `peak_ber / initial_ber`; 1 when the ramp never pushed BER up.
+    pub ber_escalation: f64,
+    pub min_eye_margin: f64,
+    /// Whether every step kept the eye margin at or above 0.3.
+    pub eye_margin_held: bool,
+    pub duration_ms: u64,
+    pub steps: Vec<BenchmarkStep>,
+}
+
+/// Health of one lane. `degraded` lanes still carry traffic at a worse BER;
+/// `down` lanes carry none. The older capitalized names are accepted on input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum LaneState {
+    #[default]
+    #[serde(rename = "up", alias = "Up")]
+    Up,
+    #[serde(rename = "degraded", alias = "Degraded")]
+    Degraded,
+    #[serde(rename = "down", alias = "Down")]
+    Down,
+}
+
+/// BER multiplier for a `Degraded` lane.
+const DEGRADED_LANE_BER_FACTOR: f64 = 10.0;
+
+/// Longest fault `POST /v1/corridors/{id}/fault` accepts (1 hour).
+const MAX_FAULT_DURATION_MS: u64 = 3_600_000;
+
+/// Ceiling for the doubling backoff of HELIOPASS and webhook retries.
+const MAX_RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// An injected fault and when it stops applying.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InjectedFault {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ber: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temp_c: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_count: Option<u64>,
+    pub until: chrono::DateTime<chrono::Utc>,
+}
+
+impl InjectedFault {
+    fn is_active(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        now < self.until
+    }
+
+    fn apply(&self, data: &mut TelemetryData) {
+        if let Some(ber) = self.ber {
+            data.ber = ber;
+        }
+        if let Some(temp_c) = self.temp_c {
+            data.temp_c = temp_c;
+        }
+        if let Some(error_count) = self.error_count {
+            data.error_count = error_count;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct AckRequest {
+    #[serde(default)]
+    pub note: String,
+}
+
+/// Partial update for an allocated corridor. Fields left out are unchanged.
+/// Immutable fields are accepted only so they can be rejected with a clear error.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CorridorPatch {
+    pub priority: Option<Priority>,
+    pub pfc: Option<bool>,
+    pub min_gbps: Option<u32>,
+    pub corridor_type: Option<serde_json::Value>,
+    pub lanes: Option<serde_json::Value>,
+    pub lambda_nm: Option<serde_json::Value>,
+    pub lambdas_per_lane: Option<serde_json::Value>,
+}
+
+pub const ANONYMOUS_USER: &str = "anonymous";
+
+fn anonymous_user() -> String {
+    ANONYMOUS_USER.to_string()
+}
+
+/// Filters accepted by `GET /v1/corridors`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ListQuery {
+    pub created_by: Option<String>,
+    /// Comma-separated projection, e.g. `id,status,achievable_gbps`.
+    pub fields: Option<String>,
+    pub status: Option<String>,
+    #[serde(rename = "type")]
+    pub corridor_type: Option<String>,
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub offset: usize,
+    /// `label.<key>=<value>` parameters, filled in by `with_labels`; all must match.
+    #[serde(skip)]
+    pub labels: HashMap<String, String>,
+}
+
+const DEFAULT_LIST_LIMIT: usize = 100;
+const MAX_LIST_LIMIT: usize = 1000;
+
+/// One page of `GET /v1/corridors`; `total` counts every match before paging.
+#[derive(Debug, Clone, Serialize)]
+pub struct CorridorPage<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CapacityQuery {
+    pub band: Band,
+    #[serde(default = "default_spacing_ghz")]
+    pub spacing_ghz: f64,
+    #[serde(default = "default_capacity_lanes")]
+    pub lanes: u32,
+}
+
+/// Corridors allocated before the margin was configurable all got 4%.
+fn legacy_rate_margin() -> f64 {
+    0.04
+}
+
+fn default_spacing_ghz() -> f64 {
+    100.0
+}
+
+fn default_capacity_lanes() -> u32 {
+    1
+}
+
+/// Query of `POST /v1/corridors`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AllocateQuery {
+    /// Answer with an SSE stream of `AllocationStage` events instead of one response.
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// Steps of an allocation, streamed by `POST /v1/corridors?stream=true`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AllocationStage {
+    Validating,
+    /// Only when the request has `attestation_required`.
+    Attesting,
+    Estimating,
+    /// Reserved for calibration on allocate; not emitted yet.
+    Calibrating,
+    /// Final event; its data is the allocated `Corridor`.
+    Active,
+}
+
+/// Called as each `AllocationStage` starts.
+pub type Progress<'a> = &'a (dyn Fn(AllocationStage) + Sync);
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FieldsQuery {
+    pub fields: Option<String>,
+    /// Fall back to the archive when the id is not a live corridor.
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+/// Serialized `Corridor` field names accepted by `fields=`.
+const CORRIDOR_FIELDS: &[&str] = &[
+    "id", "corridor_type", "lanes", "lambda_nm", "lambdas_per_lane", "min_gbps", "latency_budget_ns", "reach_mm", "mode", "qos",
+    "attestation_required", "attestation_ticket", "optimize", "max_ber", "medium_id", "labels", "modulation", "lane_rate_gbps", "fec",
+    "est_power_pj_per_bit", "achievable_gbps", "rate_margin", "ber", "eye_margin", "eye_margin_value", "created_at", "status", "created_by", "receipt",
+    "last_recalibrated_at", "uptime_seconds", "fault", "total_power_mw", "lane_status", "planned_gbps",
+];
+
+/// Parse a `fields=` projection, rejecting unknown names.
+pub fn parse_fields(fields: Option<&str>) -> Result<Option<Vec<String>>> {
+    let Some(raw) = fields else { return Ok(None) };
+    let names: Vec<String> = raw.split(',').map(|f| f.trim().to_string()).filter(|f| !f.is_empty()).collect();
+    if let Some(bad) = names.iter().find(|f| !CORRIDOR_FIELDS.contains(&f.as_str())) {
+        return Err(anyhow::anyhow!("unknown field '{}', allowed: {}", bad, CORRIDOR_FIELDS.join(",")));
+    }
+    Ok(Some(names))
+}
+
+/// Serialize a corridor plus the derived `uptime_seconds`, keeping only `fields`
+/// (all fields when `None`).
+pub fn project_corridor(c: &Corridor, fields: Option<&[String]>, now: chrono::DateTime<chrono::Utc>) -> serde_json::Value {
+    let mut value = serde_json::to_value(c).unwrap_or_default();
+    if let serde_json::Value::Object(map) = &mut value {
+        map.insert("uptime_seconds".to_string(), c.uptime_seconds(now).into());
+    }
+    match (fields, value) {
+        (Some(fields), serde_json::Value::Object(mut map)) => {
+            map.retain(|k, _| fields.iter().any(|f| f == k));
+            serde_json::Value::Object(map)
+        }
+        (_, value) => value,
+    }
+}
+
+impl ListQuery {
+    /// Pick the `label.<key>` parameters out of the raw query string.
+    pub fn with_labels(mut self, raw: HashMap<String, String>) -> Self {
+        self.labels = raw
+            .into_iter()
+            .filter_map(|(k, v)| k.strip_prefix("label.").map(|key| (key.to_string(), v)))
+            .collect();
+        self
+    }
+
+    /// Reject unknown `status`/`type` values and out-of-range limits.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(status) = &self.status {
+            status.parse::<CorridorStatus>()?;
+        }
+        if let Some(corridor_type) = &self.corridor_type {
+            corridor_type.parse::<CorridorType>()?;
+        }
+        if matches!(self.limit, Some(l) if l == 0 || l > MAX_LIST_LIMIT) {
+            return Err(anyhow::anyhow!("limit must be between 1 and {}", MAX_LIST_LIMIT));
+        }
+        Ok(())
+    }
+
+    pub fn matches(&self, c: &Corridor) -> bool {
+        self.created_by.as_deref().map(|u| c.created_by == u).unwrap_or(true)
+            && self.status.as_deref().map(|s| s.parse().is_ok_and(|s: CorridorStatus| s == c.status)).unwrap_or(true)
+            && self.corridor_type.as_deref().map(|t| t.parse().is_ok_and(|t: CorridorType| t == c.corridor_type)).unwrap_or(true)
+            && self.labels.iter().all(|(k, v)| c.labels.get(k) == Some(v))
+    }
+
+    /// Sort matches by id and cut out the requested page, without cloning the rest.
+    /// `default_limit` applies when the query has no `limit`.
+    fn page<'a>(&self, corridors: impl Iterator<Item = &'a Corridor>, default_limit: usize) -> CorridorPage<&'a Corridor> {
+        let mut matched: Vec<&Corridor> = corridors.filter(|c| self.matches(c)).collect();
+        matched.sort_by(|a, b| a.id.cmp(&b.id));
+        let total = matched.len();
+        let items = matched
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(default_limit))
+            .collect();
+        CorridorPage { items, total, offset: self.offset }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Lifecycle state, serialized in snake_case (`active`, `attestation_expired`).
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
 pub enum CorridorStatus {
+    #[serde(rename = "active")]
     Active,
+    #[serde(rename = "calibrating")]
     Calibrating,
+    #[serde(rename = "error")]
     Error,
+    #[serde(rename = "maintenance")]
     Maintenance,
+    /// The attestation ticket expired and attestd did not renew it.
+    #[serde(rename = "attestation_expired")]
+    AttestationExpired,
+    /// Deleted and kept in the archive; never set on a live corridor.
+    #[serde(rename = "decommissioned")]
+    Decommissioned,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl CorridorStatus {
+    /// The wire name, as serialized and used for the `status` metric label.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CorridorStatus::Active => "active",
+            CorridorStatus::Calibrating => "calibrating",
+            CorridorStatus::Error => "error",
+            CorridorStatus::Maintenance => "maintenance",
+            CorridorStatus::AttestationExpired => "attestation_expired",
+            CorridorStatus::Decommissioned => "decommissioned",
+        }
+    }
+}
+
+impl std::str::FromStr for CorridorStatus {
+    type Err = CorridorError;
+
+    /// Accepts the serialized names and the older capitalized ones.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "active" | "Active" => Ok(CorridorStatus::Active),
+            "calibrating" | "Calibrating" => Ok(CorridorStatus::Calibrating),
+            "error" | "Error" => Ok(CorridorStatus::Error),
+            "maintenance" | "Maintenance" => Ok(CorridorStatus::Maintenance),
+            "attestation_expired" | "AttestationExpired" => Ok(CorridorStatus::AttestationExpired),
+            "decommissioned" | "Decommissioned" => Ok(CorridorStatus::Decommissioned),
+            other => Err(CorridorError::InvalidRequest(format!(
+                "unknown status: {}, expected active|calibrating|error|maintenance|attestation_expired|decommissioned",
+                other
+            ))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CorridorStatus {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        String::deserialize(d)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A corridor status transition, streamed by `GET /v1/corridors/{id}/events`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StatusEvent {
+    pub corridor_id: String,
+    pub from: CorridorStatus,
+    pub to: CorridorStatus,
+    pub at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TelemetryData {
     pub ber: f64,
     pub temp_c: f64,
@@ -75,14 +666,103 @@ pub struct TelemetryData {
     pub error_count: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Timestamped telemetry samples for one corridor, oldest first.
+type TelemetryWindow = std::collections::VecDeque<(chrono::DateTime<chrono::Utc>, TelemetryData)>;
+
+/// Spread of one telemetry value over the sample window.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TelemetryStats {
+    pub min: f64,
+    pub max: f64,
+    pub avg: f64,
+    pub p95: f64,
+}
+
+impl TelemetryStats {
+    /// `None` for an empty window.
+    fn over(values: impl Iterator<Item = f64>) -> Option<Self> {
+        let mut values: Vec<f64> = values.collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        // Nearest-rank percentile.
+        let rank = ((values.len() as f64 * 0.95).ceil() as usize).max(1);
+        Some(Self {
+            min: values[0],
+            max: values[values.len() - 1],
+            avg: values.iter().sum::<f64>() / values.len() as f64,
+            p95: values[rank - 1],
+        })
+    }
+}
+
+/// `GET /v1/corridors/{id}/telemetry/summary`: the last `samples` telemetry
+/// readings, taken between `from` and `to`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TelemetrySummary {
+    pub samples: usize,
+    pub from: chrono::DateTime<chrono::Utc>,
+    pub to: chrono::DateTime<chrono::Utc>,
+    pub ber: TelemetryStats,
+    pub temp_c: TelemetryStats,
+    pub utilization_percent: TelemetryStats,
+}
+
+/// `GET /v1/telemetry/summary`: the latest telemetry sample of every Active corridor
+/// rolled into fleet totals. Corridors not sampled yet count toward `capacity_gbps`
+/// only.
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct FleetTelemetry {
+    pub active_corridors: usize,
+    pub sampled_corridors: usize,
+    /// Sum of `achievable_gbps` over Active corridors.
+    pub capacity_gbps: u64,
+    /// Sampled corridors' utilization weighted by their `achievable_gbps`.
+    pub utilization_percent: f64,
+    pub max_ber: Option<f64>,
+    pub max_ber_corridor: Option<String>,
+    pub max_temp_c: Option<f64>,
+    pub total_error_count: u64,
+}
+
+impl TelemetrySummary {
+    fn over(window: &TelemetryWindow) -> Option<Self> {
+        let stats = |f: fn(&TelemetryData) -> f64| TelemetryStats::over(window.iter().map(|(_, d)| f(d)));
+        Some(Self {
+            samples: window.len(),
+            from: window.front()?.0,
+            to: window.back()?.0,
+            ber: stats(|d| d.ber)?,
+            temp_c: stats(|d| d.temp_c)?,
+            utilization_percent: stats(|d| d.utilization_percent)?,
+        })
+    }
+}
+
+/// One entry of `GET /v1/corridors/{id}/telemetry/lanes`, with the same values
+/// as the `corridor_lane_*` gauges.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct LaneTelemetry {
+    pub lane: usize,
+    pub lambda_nm: u32,
+    pub state: LaneState,
+    pub ber: f64,
+    pub temp_c: f64,
+    pub power_pj_per_bit: f64,
+    pub utilization_percent: f64,
+    pub error_count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RecalibrateRequest {
     pub target_ber: f64,
     pub ambient_profile: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RecalibrateResponse {
+    /// `converged` or `not_converged` (target BER out of reach).
     pub status: String,
     pub converged: bool,
     pub bias_voltages: Vec<f64>,
@@ -92,86 +772,1114 @@ pub struct RecalibrateResponse {
     pub final_ber: f64,
     pub final_eye_margin: f64,
     pub power_savings: f64,
+    pub source: CalibrationSource,
+}
+
+/// One entry of `GET /v1/corridors/{id}/recalibrations`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RecalibrationRecord {
+    pub at: chrono::DateTime<chrono::Utc>,
+    #[serde(flatten)]
+    pub result: RecalibrateResponse,
+}
+
+/// Whether a recalibration result came from HELIOPASS or the local synthetic fallback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CalibrationSource {
+    Heliopass,
+    Fallback,
+}
+
+/// What this daemon supports, served at `GET /v1/capabilities`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Capabilities {
+    pub corridor_types: Vec<CorridorType>,
+    pub modulation_formats: Vec<Modulation>,
+    pub lane_rates_gbps: Vec<u32>,
+    pub bands: Vec<Band>,
+    pub optimize: Vec<OptimizeFor>,
+    pub limits: CapabilityLimits,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CapabilityLimits {
+    /// Keyed by corridor type name.
+    pub max_reach_mm: HashMap<String, u32>,
+}
+
+/// Per-type reach ceilings, configurable via `CORRD_MAX_REACH_MM_SI` / `CORRD_MAX_REACH_MM_CARBON`.
+#[derive(Debug, Clone)]
+pub struct ReachLimits {
+    pub si_mm: u32,
+    pub carbon_mm: u32,
+}
+
+impl ReachLimits {
+    pub fn from_env() -> Self {
+        let get = |key: &str, default: u32| env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default);
+        Self {
+            si_mm: get("CORRD_MAX_REACH_MM_SI", 150),
+            carbon_mm: get("CORRD_MAX_REACH_MM_CARBON", 600),
+        }
+    }
+
+    pub fn for_type(&self, corridor_type: &CorridorType) -> u32 {
+        match corridor_type {
+            CorridorType::SiCorridor => self.si_mm,
+            CorridorType::CarbonCorridor => self.carbon_mm,
+        }
+    }
+}
+
+/// Speed of light in vacuum, mm/ns.
+const C_MM_PER_NS: f64 = 299.792458;
+/// Group index of a silicon photonic waveguide.
+const SI_GROUP_INDEX: f64 = 4.2;
+/// Group index of a carbon-core waveguide.
+const CARBON_GROUP_INDEX: f64 = 2.4;
+/// Fixed serializer/deserializer latency at both ends of a corridor (ns).
+const SERDES_LATENCY_NS: f64 = 10.0;
+
+/// Lowest end-to-end latency physically possible over `reach_mm` of this medium.
+pub fn min_latency_ns(corridor_type: &CorridorType, reach_mm: u32) -> f64 {
+    let group_index = match corridor_type {
+        CorridorType::SiCorridor => SI_GROUP_INDEX,
+        CorridorType::CarbonCorridor => CARBON_GROUP_INDEX,
+    };
+    SERDES_LATENCY_NS + reach_mm as f64 * group_index / C_MM_PER_NS
+}
+
+/// Planning cost charged per provisioned lane (arbitrary units).
+const COST_UNITS_PER_LANE: f64 = 10.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CorridorEstimate {
+    pub modulation: Modulation,
+    pub lane_rate_gbps: u32,
+    pub fec: bool,
+    pub ber: f64,
+    pub achievable_gbps: u32,
+    pub rate_margin: f64,
+    pub power_pj_per_bit: f64,
+    pub power_mw: f64,
+    pub cost_units: f64,
+    pub lanes: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BatchItemResult {
+    pub index: usize,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<CorridorEstimate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WavelengthConflict {
+    pub lambda_nm: u32,
+    pub indices: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BatchEstimate {
+    pub total_power_mw: f64,
+    pub total_cost_units: f64,
+    pub total_lanes: u32,
+    pub wavelength_conflicts: Vec<WavelengthConflict>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BatchValidation {
+    pub valid: bool,
+    pub results: Vec<BatchItemResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<BatchEstimate>,
+}
+
+/// Why `allocate_batch` created nothing.
+#[derive(Debug)]
+pub enum BatchError {
+    /// Per-request admission results, at least one failed.
+    Items(Vec<BatchItemResult>),
+    /// The batch as a whole was refused, e.g. over the power budget.
+    Rejected(anyhow::Error),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchValidateQuery {
+    #[serde(default)]
+    pub estimate: bool,
+}
+
+/// Carbon lanes run this much faster than silicon at the same operating point.
+const CARBON_RATE_SCALE: f64 = 1.5;
+/// Extra pJ/bit carbon pays, scaled by the square of the lane rate in 100 Gb/s units.
+const CARBON_PJ_PER_100G_SQ: f64 = 0.3;
+/// Lane-to-lane power spread reported in lane metrics (± pJ/bit).
+const SI_LANE_POWER_STEP: f64 = 0.005;
+const CARBON_LANE_POWER_STEP: f64 = 0.02;
+/// Lane-to-lane BER spread reported in lane metrics (± fraction).
+const LANE_BER_SPREAD: f64 = 0.05;
+/// Lane-to-lane temperature spread reported in lane metrics (± °C).
+const LANE_TEMP_SPREAD_C: f64 = 0.5;
+
+/// Extra margin per doubling of the lane count, as a fraction of the base margin.
+const LANE_MARGIN_STEP: f64 = 0.25;
+/// Carbon's margin relative to silicon's; headroom there costs more power.
+const CARBON_MARGIN_SCALE: f64 = 0.75;
+
+/// Base headroom over `min_gbps`, from `CORRD_RATE_MARGIN` (default 0.04).
+fn base_rate_margin() -> f64 {
+    env::var("CORRD_RATE_MARGIN")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|m: &f64| (0.0..=1.0).contains(m))
+        .unwrap_or(0.04)
+}
+
+/// Headroom for a corridor: the base margin for one silicon lane, growing with the
+/// log of the lane count so each added lane buys less.
+fn rate_margin(lanes: u32, corridor_type: &CorridorType) -> f64 {
+    let lane_factor = 1.0 + (lanes.max(1) as f64).log2() * LANE_MARGIN_STEP;
+    let medium = match corridor_type {
+        CorridorType::SiCorridor => 1.0,
+        CorridorType::CarbonCorridor => CARBON_MARGIN_SCALE,
+    };
+    base_rate_margin() * lane_factor * medium
+}
+
+/// `margin` over the floor, limited by what the chosen lane rate can carry on this medium.
+fn estimate_achievable_gbps(min_gbps: u32, margin: f64, plan: &link::LinkPlan, corridor_type: &CorridorType) -> u32 {
+    let ceiling = match corridor_type {
+        CorridorType::SiCorridor => plan.capacity_gbps as f64,
+        CorridorType::CarbonCorridor => plan.capacity_gbps as f64 * CARBON_RATE_SCALE,
+    };
+    ((min_gbps as f64 * (1.0 + margin)) as u32).min(ceiling as u32)
+}
+
+/// Energy per bit for `plan` on this medium; carbon's cost climbs with lane rate.
+fn estimate_power_pj_per_bit(plan: &link::LinkPlan, corridor_type: &CorridorType) -> f64 {
+    match corridor_type {
+        CorridorType::SiCorridor => plan.est_power_pj_per_bit,
+        CorridorType::CarbonCorridor => {
+            let rate = plan.lane_rate_gbps as f64 / 100.0;
+            plan.est_power_pj_per_bit + CARBON_PJ_PER_100G_SQ * rate * rate
+        }
+    }
+}
+
+/// Best BER improvement the synthetic calibrator finds over a corridor's modelled BER.
+const SYNTHETIC_CALIBRATION_GAIN: f64 = 10.0;
+/// Iteration budget the synthetic calibrator reports using when it gives up.
+const SYNTHETIC_CALIBRATION_TIMEOUT_MS: u64 = 1000;
+
+/// Local stand-in for HELIOPASS. Bias tuning can lower the allocation-time BER
+/// (which already reflects reach, lane load and wavelength spread) by at most
+/// `SYNTHETIC_CALIBRATION_GAIN`; a `target_ber` below that floor doesn't converge.
+fn synthetic_calibration(corridor: &Corridor, target_ber: f64) -> RecalibrateResponse {
+    let floor = corridor.ber / SYNTHETIC_CALIBRATION_GAIN;
+    let converged = target_ber >= floor;
+    let lanes = corridor.lambda_nm.len();
+    RecalibrateResponse {
+        status: if converged { "converged" } else { "not_converged" }.to_string(),
+        converged,
+        bias_voltages: vec![1.2; lanes],
+        lambda_shifts: vec![0.0; lanes],
+        laser_power_adjust: vec![0.0; lanes],
+        convergence_time_ms: if converged { 150 } else { SYNTHETIC_CALIBRATION_TIMEOUT_MS },
+        final_ber: floor,
+        final_eye_margin: link::eye_margin(floor, corridor.achievable_gbps, corridor.min_gbps),
+        power_savings: if converged { 10.0 } else { 0.0 },
+        source: CalibrationSource::Fallback,
+    }
+}
+
+/// Worst BER the model will report; beyond this the link is unusable anyway.
+const BER_CEILING: f64 = 0.5;
+
+fn channel_penalty(req: &CorridorRequest) -> f64 {
+    link::channel_penalty(req.reach_mm, req.lanes, req.min_gbps, &req.lambda_nm)
+}
+
+/// Plan the link for `req`, tightening `max_ber` by the channel penalty so the
+/// reported BER still honours it.
+fn plan_for(req: &CorridorRequest) -> Result<link::LinkPlan> {
+    let penalty = channel_penalty(req);
+    plan_link(req.lanes, req.min_gbps, req.optimize, req.max_ber.map(|m| m / penalty)).map_err(|e| {
+        anyhow::anyhow!("{} (before a {:.1}x penalty for reach, lane load and wavelength spread)", e, penalty)
+    })
+}
+
+/// Expected BER for a request: the planned lane's BER degraded by reach, per-lane
+/// load and wavelength spread. Deterministic for a given request.
+pub fn estimate_ber(req: &CorridorRequest) -> f64 {
+    let base = plan_for(req).map(|p| p.est_ber).unwrap_or(1.0e-12);
+    (base * channel_penalty(req)).min(BER_CEILING)
+}
+
+/// Modelled BER of `c` carrying `offered_gbps`: its estimate rescaled by how the
+/// channel penalty's per-lane load term moves from `min_gbps` to the offered load,
+/// so at `min_gbps` it is exactly the allocation estimate.
+fn ber_at_load(c: &Corridor, offered_gbps: u32) -> f64 {
+    let penalty = |gbps| link::channel_penalty(c.reach_mm, c.lanes, gbps, &c.lambda_nm);
+    (c.ber * penalty(offered_gbps) / penalty(c.min_gbps)).min(BER_CEILING)
+}
+
+/// Projected rate, power and cost for a request, without allocating anything.
+pub fn estimate_corridor(req: &CorridorRequest) -> Result<CorridorEstimate> {
+    let plan = plan_for(req)?;
+    let rate_margin = rate_margin(req.lanes, &req.corridor_type);
+    let achievable_gbps = estimate_achievable_gbps(req.min_gbps, rate_margin, &plan, &req.corridor_type);
+    let power_pj_per_bit = estimate_power_pj_per_bit(&plan, &req.corridor_type);
+    // 1 pJ/bit at 1 Gb/s is 1 mW
+    let power_mw = power_pj_per_bit * achievable_gbps as f64;
+    Ok(CorridorEstimate {
+        modulation: plan.modulation,
+        lane_rate_gbps: plan.lane_rate_gbps,
+        fec: plan.fec,
+        ber: estimate_ber(req),
+        achievable_gbps,
+        rate_margin,
+        power_pj_per_bit,
+        power_mw,
+        cost_units: req.lanes as f64 * COST_UNITS_PER_LANE,
+        lanes: req.lanes,
+    })
+}
+
+/// Plausible optical window (O through L band) in nm, inclusive.
+const OPTICAL_BAND_NM: (u32, u32) = (1260, 1625);
+
+/// Most lanes a single corridor may request.
+const MAX_LANES: u32 = 64;
+
+/// Most wavelengths one lane may multiplex.
+const MAX_LAMBDAS_PER_LANE: u32 = 8;
+
+/// `mode` under which every `lambda_nm` must sit on the ITU 100 GHz grid.
+const DWDM_MODE: &str = "dwdm";
+
+const MAX_LABELS: usize = 16;
+const MAX_LABEL_LEN: usize = 63;
+/// Label keys `CORRD_METRIC_LABELS` may export on `corridor_labels`.
+const MAX_METRIC_LABELS: usize = 4;
+
+fn check_label_key(key: &str) -> Result<()> {
+    let valid = key.len() <= MAX_LABEL_LEN
+        && key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        return Err(anyhow::anyhow!(
+            "label key '{}' must be 1-{} of [a-z0-9_], starting with a letter",
+            key, MAX_LABEL_LEN
+        ));
+    }
+    Ok(())
+}
+
+/// At most `MAX_LABELS` labels; keys are Prometheus-safe, values at most
+/// `MAX_LABEL_LEN` of `[A-Za-z0-9_.-]`, so exporting them can't blow up a scrape.
+pub fn validate_labels(labels: &HashMap<String, String>) -> Result<()> {
+    if labels.len() > MAX_LABELS {
+        return Err(anyhow::anyhow!("at most {} labels are allowed", MAX_LABELS));
+    }
+    for (key, value) in labels {
+        check_label_key(key)?;
+        if value.len() > MAX_LABEL_LEN || !value.chars().all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c)) {
+            return Err(anyhow::anyhow!(
+                "label {} value must be at most {} of [A-Za-z0-9_.-]",
+                key, MAX_LABEL_LEN
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Label keys named by `CORRD_METRIC_LABELS` (comma-separated), exported on
+/// `corridor_labels` as `label_<key>`.
+fn metric_label_keys() -> Result<Vec<String>> {
+    let keys: Vec<String> = env::var("CORRD_METRIC_LABELS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .map(str::to_string)
+        .collect();
+    if keys.len() > MAX_METRIC_LABELS {
+        return Err(anyhow::anyhow!("CORRD_METRIC_LABELS names more than {} keys", MAX_METRIC_LABELS));
+    }
+    for key in &keys {
+        check_label_key(key).map_err(|e| anyhow::anyhow!("CORRD_METRIC_LABELS: {}", e))?;
+    }
+    Ok(keys)
+}
+
+/// Everything about a request's shape that doesn't depend on service configuration:
+/// non-zero `min_gbps` and `reach_mm`, a non-empty and consistent wavelength plan,
+/// and well-formed labels. Each error names the offending field.
+pub fn validate_request(req: &CorridorRequest) -> Result<()> {
+    if req.min_gbps == 0 {
+        return Err(anyhow::anyhow!("min_gbps must be at least 1"));
+    }
+    if req.reach_mm == 0 {
+        return Err(anyhow::anyhow!("reach_mm must be at least 1"));
+    }
+    if req.lambda_nm.is_empty() {
+        return Err(anyhow::anyhow!("lambda_nm must list at least one wavelength"));
+    }
+    validate_lambda_plan(req)?;
+    validate_labels(&req.labels)
+}
+
+/// Check the wavelength plan: at most `MAX_LANES` lanes, `lambdas_per_lane`
+/// wavelengths per lane (default one), inside the optical window, no repeats.
+pub fn validate_lambda_plan(req: &CorridorRequest) -> Result<()> {
+    if req.lanes == 0 || req.lanes > MAX_LANES {
+        return Err(anyhow::anyhow!("lanes must be between 1 and {}", MAX_LANES));
+    }
+    let per_lane = req.lambdas_per_lane.unwrap_or(1);
+    if per_lane == 0 || per_lane > MAX_LAMBDAS_PER_LANE {
+        return Err(anyhow::anyhow!("lambdas_per_lane must be between 1 and {}", MAX_LAMBDAS_PER_LANE));
+    }
+    let max_lambdas = (MAX_LANES * MAX_LAMBDAS_PER_LANE) as usize;
+    if req.lambda_nm.len() > max_lambdas {
+        return Err(anyhow::anyhow!("lambda_nm has {} wavelengths, at most {} are allowed", req.lambda_nm.len(), max_lambdas));
+    }
+    let expected = (req.lanes * per_lane) as usize;
+    if req.lambda_nm.len() != expected {
+        return Err(anyhow::anyhow!(
+            "lambda_nm has {} wavelengths but lanes is {} with {} per lane; provide exactly {}",
+            req.lambda_nm.len(), req.lanes, per_lane, expected
+        ));
+    }
+    let (lo, hi) = OPTICAL_BAND_NM;
+    if let Some(bad) = req.lambda_nm.iter().find(|nm| **nm < lo || **nm > hi) {
+        return Err(anyhow::anyhow!("lambda_nm {} is outside the {}-{} nm optical band", bad, lo, hi));
+    }
+    let mut seen = HashSet::new();
+    if let Some(dup) = req.lambda_nm.iter().find(|nm| !seen.insert(**nm)) {
+        return Err(anyhow::anyhow!("lambda_nm {} is listed more than once", dup));
+    }
+    if req.mode.eq_ignore_ascii_case(DWDM_MODE) {
+        if let Some(bad) = req.lambda_nm.iter().find(|nm| !wavelength::is_valid_channel(**nm)) {
+            return Err(anyhow::anyhow!(
+                "lambda_nm {} is off the ITU 100 GHz grid (nearest channel {:.2} nm), required in dwdm mode",
+                bad, wavelength::nearest_channel_nm(*bad)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Wavelengths claimed by more than one request in the batch.
+fn batch_wavelength_conflicts(reqs: &[CorridorRequest]) -> Vec<WavelengthConflict> {
+    let mut claims: std::collections::BTreeMap<u32, Vec<usize>> = std::collections::BTreeMap::new();
+    for (i, req) in reqs.iter().enumerate() {
+        for lambda in &req.lambda_nm {
+            let owners = claims.entry(*lambda).or_default();
+            if !owners.contains(&i) {
+                owners.push(i);
+            }
+        }
+    }
+    claims
+        .into_iter()
+        .filter(|(_, indices)| indices.len() > 1)
+        .map(|(lambda_nm, indices)| WavelengthConflict { lambda_nm, indices })
+        .collect()
 }
 
 pub struct CorridorService {
     corridors: Arc<RwLock<HashMap<String, Corridor>>>,
-    next_id: Arc<RwLock<u32>>,
-    heliopass_url: String,
-    attestd_url: String,
+    next_id: Arc<RwLock<u64>>,
+    heliopass_url: http::BaseUrl,
+    heliopass_timeout: std::time::Duration,
+    heliopass_attempts: u32,
+    heliopass_backoff: std::time::Duration,
+    verifier: Arc<dyn AttestationVerifier>,
+    attest_cache: AttestationCache,
+    idempotency: IdempotencyCache,
+    alerts: AlertEngine,
+    status_events: tokio::sync::broadcast::Sender<StatusEvent>,
+    reach_limits: ReachLimits,
+    signer: ReceiptSigner,
+    latency: LatencyTracker,
+    hooks: Hooks,
+    store: Option<StateStore>,
+    /// Deleted corridors, kept for audit until purged. Only touched under the
+    /// `corridors` write lock and persisted alongside the live ones.
+    archived: std::sync::Mutex<HashMap<String, Corridor>>,
     m_lane_ber: GaugeVec,
     m_lane_temp: GaugeVec,
     m_lane_power: GaugeVec,
     m_lane_util: GaugeVec,
     m_lane_err: GaugeVec,
+    m_lane_eye: GaugeVec,
+    m_lane_up: GaugeVec,
+    m_reconcile_fixes: IntCounter,
+    m_status_seconds: CounterVec,
+    m_corridor_total: IntGauge,
+    m_corridor_status: IntGaugeVec,
+    m_allocate_requests: IntCounterVec,
+    m_uptime: GaugeVec,
+    m_power: GaugeVec,
+    m_labels: IntGaugeVec,
+    m_attestation_expired: IntGauge,
+    /// Keys exported on `m_labels`, from `CORRD_METRIC_LABELS`.
+    metric_label_keys: Vec<String>,
+    /// Base seed for simulated lane variation, from `CORRD_SIM_SEED` (default 0).
+    sim_seed: u64,
+    /// Fleet power ceiling from `CORRD_POWER_BUDGET_MW`; unlimited when unset.
+    power_budget_mw: Option<f64>,
+    clock: Arc<dyn Clock>,
+    /// Every collector this service exports; nothing goes in the global default
+    /// registry, so several services in one process never see each other's series.
+    /// The one shared collector is `http::request_duration`, which times downstream
+    /// calls made outside any service and so is process-wide by nature.
+    registry: prometheus::Registry,
+    /// Recent recalibrations per corridor, oldest first; not persisted.
+    recalibrations: std::sync::Mutex<HashMap<String, std::collections::VecDeque<RecalibrationRecord>>>,
+    /// Entries kept per corridor, from `CORRD_RECALIBRATION_HISTORY` (default 20).
+    recalibration_history: usize,
+    /// Per-corridor calibration locks; a recalibrate that can't take its lock
+    /// gets a 409 instead of racing the one in flight.
+    calibrations: std::sync::Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    /// `(medium_id, lambda_nm)` → holding corridor. Only touched under the
+    /// `corridors` write lock, so it always matches the corridor map.
+    reservations: std::sync::Mutex<HashMap<(String, u32), String>>,
+    /// Recent telemetry samples per corridor, oldest first; not persisted.
+    telemetry_samples: std::sync::Mutex<HashMap<String, TelemetryWindow>>,
+    /// Samples kept per corridor, from `CORRD_TELEMETRY_WINDOW` (default 60).
+    telemetry_window: usize,
+    /// Share of lanes that may be `Down` before the corridor goes to `Error`, from
+    /// `CORRD_MAX_DOWN_LANE_FRACTION` (default 0.5).
+    max_down_lane_fraction: f64,
+}
+
+/// The `(medium_id, lambda_nm)` reservations a corridor holds.
+fn lambda_reservations(medium_id: Option<&str>, lambda_nm: &[u32]) -> Vec<(String, u32)> {
+    match medium_id {
+        Some(medium) => lambda_nm.iter().map(|nm| (medium.to_string(), *nm)).collect(),
+        None => Vec::new(),
+    }
+}
+
+/// One item of the `CORRD_BOOTSTRAP_CORRIDORS` file: a `CorridorRequest` with an
+/// optional `bootstrap_id` that keeps its identity if the file is reordered.
+#[derive(Debug, Deserialize)]
+struct BootstrapEntry {
+    #[serde(default)]
+    bootstrap_id: Option<String>,
+    #[serde(flatten)]
+    request: CorridorRequest,
+}
+
+/// What `admit_request` hands to `insert_corridor`.
+struct Admission {
+    estimate: CorridorEstimate,
+    attestation_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+fn fleet_power_mw<'a>(corridors: impl Iterator<Item = &'a Corridor>) -> f64 {
+    corridors.fold(0.0, |sum, c| sum + c.total_power_mw)
+}
+
+fn in_maintenance(id: &str) -> anyhow::Error {
+    CorridorError::Conflict(format!("corridor {} is {}; resume it first", id, CorridorStatus::Maintenance.as_str())).into()
+}
+
+/// Only the attestation sweep brings an `AttestationExpired` corridor back, once
+/// attestd renews its ticket.
+fn attestation_expired(id: &str) -> anyhow::Error {
+    CorridorError::Conflict(format!("corridor {} is {} until its ticket is renewed", id, CorridorStatus::AttestationExpired.as_str())).into()
+}
+
+/// Namespace for corrd's `corridor_*` metrics, from `CORRD_METRIC_PREFIX` (default
+/// `corridor`); set it when several services share a registry or scrape target.
+/// Changing it renames every such series, so dashboards and alert rules written
+/// against `corridor_*` must be updated to match.
+fn metric_prefix() -> &'static str {
+    static PREFIX: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    PREFIX.get_or_init(|| env::var("CORRD_METRIC_PREFIX").ok().filter(|v| !v.is_empty()).unwrap_or_else(|| "corridor".to_string()))
+}
+
+/// Refuse a `CORRD_METRIC_PREFIX` that can't start a Prometheus metric name.
+fn check_metric_prefix() -> Result<()> {
+    let prefix = metric_prefix();
+    let valid = prefix.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == ':')
+        && prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':');
+    if !valid {
+        return Err(anyhow::anyhow!("CORRD_METRIC_PREFIX '{}' must match [a-zA-Z_:][a-zA-Z0-9_:]*", prefix));
+    }
+    Ok(())
+}
+
+/// Metric options carrying the deployment-wide `env` label when `CORRD_ENV` is set.
+/// It is a constant label, so it adds no per-series cardinality. A leading
+/// `corridor_` in `name` is replaced by `metric_prefix()`.
+fn metric_opts(name: &str, help: &str) -> prometheus::Opts {
+    let name = match name.strip_prefix("corridor_") {
+        Some(rest) => format!("{}_{}", metric_prefix(), rest),
+        None => name.to_string(),
+    };
+    let opts = prometheus::Opts::new(name, help);
+    match env::var("CORRD_ENV") {
+        Ok(deploy_env) if !deploy_env.is_empty() => opts.const_label("env", deploy_env),
+        _ => opts,
+    }
+}
+
+/// Register `metric` with `registry` and hand it back for the service to keep.
+fn register<M: prometheus::core::Collector + Clone + 'static>(registry: &prometheus::Registry, metric: M) -> Result<M> {
+    registry.register(Box::new(metric.clone()))?;
+    Ok(metric)
+}
+
+/// (corridor_id, lane, lambda_nm) label values of one lane series.
+type LaneLabels = (String, String, String);
+
+fn lane_labels(corridor: &Corridor) -> impl Iterator<Item = LaneLabels> + '_ {
+    corridor
+        .lambda_nm
+        .iter()
+        .enumerate()
+        .map(|(i, lambda)| (corridor.id.clone(), corridor.lane_of(i).to_string(), lambda.to_string()))
+}
+
+fn series_labels(gauge: &GaugeVec) -> HashSet<LaneLabels> {
+    use prometheus::core::Collector;
+    gauge
+        .collect()
+        .iter()
+        .flat_map(|mf| mf.get_metric().iter())
+        .map(|m| {
+            let get = |name: &str| {
+                m.get_label().iter().find(|l| l.get_name() == name).map(|l| l.get_value().to_string()).unwrap_or_default()
+            };
+            (get("corridor_id"), get("lane"), get("lambda_nm"))
+        })
+        .collect()
 }
 
 impl CorridorService {
-    pub fn new() -> Self {
-        let heliopass_url = env::var("HELIOPASS_URL").unwrap_or_else(|_| "http://localhost:8082".to_string());
-        let attestd_url = env::var("ATTESTD_URL").unwrap_or_else(|_| "http://localhost:8084".to_string());
-        let m_lane_ber = prometheus::register_gauge_vec!(
-            "corridor_lane_ber",
-            "Per-lane BER",
+    pub fn new() -> Result<Self> {
+        Self::with_verifier(Box::new(AttestdVerifier::from_env()?))
+    }
+
+    /// Metrics go to a registry owned by this service rather than the process-wide
+    /// default, so several services can coexist in one process.
+    pub fn with_verifier(verifier: Box<dyn AttestationVerifier>) -> Result<Self> {
+        check_metric_prefix()?;
+        let signer = ReceiptSigner::from_env()?;
+        let registry = prometheus::Registry::new();
+        let heliopass_url = http::parse_base_url(&env::var("HELIOPASS_URL").unwrap_or_else(|_| "http://localhost:8082".to_string()))
+            .map_err(|e| anyhow::anyhow!("HELIOPASS_URL: {}", e))?;
+        let m_lane_ber = register(&registry, GaugeVec::new(
+            metric_opts("corridor_lane_ber", "Per-lane BER"),
             &["corridor_id", "lane", "lambda_nm"]
-        ).unwrap();
-        let m_lane_temp = prometheus::register_gauge_vec!(
-            "corridor_lane_temp_c",
-            "Per-lane temperature (C)",
+        )?)?;
+        let m_lane_temp = register(&registry, GaugeVec::new(
+            metric_opts("corridor_lane_temp_c", "Per-lane temperature (C)"),
             &["corridor_id", "lane", "lambda_nm"]
-        ).unwrap();
-        let m_lane_power = prometheus::register_gauge_vec!(
-            "corridor_lane_power_pj_per_bit",
-            "Per-lane power (pJ/bit)",
+        )?)?;
+        let m_lane_power = register(&registry, GaugeVec::new(
+            metric_opts("corridor_lane_power_pj_per_bit", "Per-lane power (pJ/bit)"),
             &["corridor_id", "lane", "lambda_nm"]
-        ).unwrap();
-        let m_lane_util = prometheus::register_gauge_vec!(
-            "corridor_lane_utilization_percent",
-            "Per-lane utilization (%)",
+        )?)?;
+        let m_lane_util = register(&registry, GaugeVec::new(
+            metric_opts("corridor_lane_utilization_percent", "Per-lane utilization (%)"),
             &["corridor_id", "lane", "lambda_nm"]
-        ).unwrap();
-        let m_lane_err = prometheus::register_gauge_vec!(
-            "corridor_lane_error_count",
-            "Per-lane error count",
+        )?)?;
+        let m_lane_err = register(&registry, GaugeVec::new(
+            metric_opts("corridor_lane_error_count", "Per-lane error count"),
             &["corridor_id", "lane", "lambda_nm"]
-        ).unwrap();
-        Self {
-            corridors: Arc::new(RwLock::new(HashMap::new())),
-            next_id: Arc::new(RwLock::new(1)),
+        )?)?;
+        let m_lane_eye = register(&registry, GaugeVec::new(
+            metric_opts("corridor_lane_eye_margin", "Per-lane normalized eye margin (0-1)"),
+            &["corridor_id", "lane", "lambda_nm"]
+        )?)?;
+        let m_lane_up = register(&registry, GaugeVec::new(
+            metric_opts("corridor_lane_up", "1 while the lane carries traffic (Up or Degraded), 0 when Down"),
+            &["corridor_id", "lane", "lambda_nm"]
+        )?)?;
+        let m_reconcile_fixes = register(&registry, IntCounter::with_opts(metric_opts(
+            "corrd_metric_reconcile_fixes_total",
+            "Lane series removed or re-emitted by the metrics reconciler"
+        ))?)?;
+        let m_status_seconds = register(&registry, CounterVec::new(
+            metric_opts("corrd_corridor_status_seconds_total", "Cumulative time corridors spent in each status (s)"),
+            &["status"]
+        )?)?;
+        let m_corridor_total = register(&registry, IntGauge::with_opts(metric_opts(
+            "corridor_total",
+            "Corridors currently allocated"
+        ))?)?;
+        let m_corridor_status = register(&registry, IntGaugeVec::new(
+            metric_opts("corridor_status", "Corridors currently in each status"),
+            &["status"]
+        )?)?;
+        let m_allocate_requests = register(&registry, IntCounterVec::new(
+            metric_opts("corridor_allocate_requests_total", "Allocation requests by outcome"),
+            &["outcome"]
+        )?)?;
+        let m_uptime = register(&registry, GaugeVec::new(
+            metric_opts("corridor_uptime_seconds", "Seconds since the corridor was allocated"),
+            &["corridor_id"]
+        )?)?;
+        let metric_label_keys = metric_label_keys()?;
+        let label_names: Vec<String> = std::iter::once("corridor_id".to_string())
+            .chain(metric_label_keys.iter().map(|k| format!("label_{}", k)))
+            .collect();
+        let m_labels = register(&registry, IntGaugeVec::new(
+            metric_opts("corridor_labels", "Always 1; the corridor's labels named in CORRD_METRIC_LABELS"),
+            &label_names.iter().map(String::as_str).collect::<Vec<_>>()
+        )?)?;
+        let m_attestation_expired = register(&registry, IntGauge::with_opts(metric_opts(
+            "corridor_attestation_expired",
+            "Corridors whose attestation ticket expired without being renewed"
+        ))?)?;
+        let m_power = register(&registry, GaugeVec::new(
+            metric_opts("corridor_power_mw", "Corridor power draw at the achievable rate (mW)"),
+            &["corridor_id"]
+        )?)?;
+        let m_dep_latency = register(&registry, prometheus::HistogramVec::new(
+            prometheus::HistogramOpts::from(metric_opts(
+                "corrd_dependency_latency_seconds",
+                "Latency of HELIOPASS/attestd calls (s)"
+            )),
+            &["dependency"]
+        )?)?;
+        register(&registry, http::request_duration().clone())?;
+        let build = version::build_info();
+        register(&registry, IntGaugeVec::new(
+            metric_opts("corridor_build_info", "Always 1; labels identify the running corrd build"),
+            &["version", "git_sha"]
+        )?)?
+        .with_label_values(&[build.version, build.git_sha])
+        .set(1);
+        let latency_window: usize = env::var("CORRD_LATENCY_WINDOW").ok().and_then(|v| v.parse().ok()).unwrap_or(100);
+        let degraded_p95_ms: u64 = env::var("CORRD_DEGRADED_P95_MS").ok().and_then(|v| v.parse().ok()).unwrap_or(500);
+        let store = StateStore::from_env();
+        let mut restored = store.as_ref().map(|s| s.load()).unwrap_or_default();
+        // State written before power accounting and numeric eye margins lacks both.
+        for c in restored.values_mut() {
+            c.total_power_mw = c.power_mw();
+            c.eye_margin_value = link::eye_margin(c.ber, c.achievable_gbps, c.min_gbps);
+            c.lane_status.resize(c.lanes as usize, LaneState::Up);
+            if c.planned_gbps == 0 {
+                c.planned_gbps = c.achievable_gbps;
+            }
+        }
+        let next_id = persist::next_id_after(&restored);
+        let (archived, restored): (HashMap<_, _>, HashMap<_, _>) =
+            restored.into_iter().partition(|(_, c)| c.status == CorridorStatus::Decommissioned);
+        let restored_list: Vec<Corridor> = restored.values().cloned().collect();
+        let reservations = restored
+            .values()
+            .flat_map(|c| lambda_reservations(c.medium_id.as_deref(), &c.lambda_nm).into_iter().map(move |k| (k, c.id.clone())))
+            .collect();
+        let service = Self {
+            corridors: Arc::new(RwLock::new(restored)),
+            next_id: Arc::new(RwLock::new(next_id)),
             heliopass_url,
-            attestd_url,
+            heliopass_timeout: http::timeout_from_env("HELIOPASS_TIMEOUT_MS"),
+            heliopass_attempts: env::var("HELIOPASS_RETRY_ATTEMPTS").ok().and_then(|v| v.parse().ok()).unwrap_or(3u32).max(1),
+            heliopass_backoff: std::time::Duration::from_millis(
+                env::var("HELIOPASS_RETRY_BACKOFF_MS").ok().and_then(|v| v.parse().ok()).unwrap_or(100),
+            ),
+            verifier: Arc::from(verifier),
+            attest_cache: AttestationCache::from_env(),
+            idempotency: IdempotencyCache::from_env(),
+            alerts: AlertEngine::new(AlertThresholds::from_env()),
+            status_events: tokio::sync::broadcast::channel(256).0,
+            reach_limits: ReachLimits::from_env(),
+            signer,
+            hooks: Hooks::from_env(&registry)?,
+            store,
+            archived: std::sync::Mutex::new(archived),
+            latency: LatencyTracker::new(latency_window, std::time::Duration::from_millis(degraded_p95_ms), m_dep_latency),
             m_lane_ber,
             m_lane_temp,
             m_lane_power,
             m_lane_util,
             m_lane_err,
+            m_lane_eye,
+            m_lane_up,
+            m_reconcile_fixes,
+            m_status_seconds,
+            m_corridor_total,
+            m_corridor_status,
+            m_allocate_requests,
+            m_uptime,
+            m_power,
+            m_labels,
+            m_attestation_expired,
+            metric_label_keys,
+            sim_seed: env::var("CORRD_SIM_SEED").ok().and_then(|v| v.parse().ok()).unwrap_or(0),
+            power_budget_mw: env::var("CORRD_POWER_BUDGET_MW").ok().and_then(|v| v.parse().ok()),
+            clock: Arc::new(SystemClock),
+            registry,
+            recalibrations: std::sync::Mutex::new(HashMap::new()),
+            calibrations: std::sync::Mutex::new(HashMap::new()),
+            recalibration_history: env::var("CORRD_RECALIBRATION_HISTORY").ok().and_then(|v| v.parse().ok()).unwrap_or(20),
+            reservations: std::sync::Mutex::new(reservations),
+            telemetry_samples: std::sync::Mutex::new(HashMap::new()),
+            telemetry_window: env::var("CORRD_TELEMETRY_WINDOW").ok().and_then(|v| v.parse().ok()).unwrap_or(60).max(1),
+            max_down_lane_fraction: env::var("CORRD_MAX_DOWN_LANE_FRACTION")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|f: &f64| (0.0..=1.0).contains(f))
+                .unwrap_or(0.5),
+        };
+        service.update_fleet_metrics(&restored_list);
+        if !restored_list.is_empty() {
+            tracing::info!("restored {} corridors from state file", restored_list.len());
+            for c in &restored_list {
+                service.update_lane_metrics(c, None);
+            }
+        }
+        Ok(service)
+    }
+
+    /// Replace the wall clock, e.g. with a `MockClock` to step time in tests. Every
+    /// time-dependent path (timestamps, fault expiry, alert cooldowns, attestation
+    /// cache TTLs, webhook `ts`) reads the service clock. Unused by the binary itself.
+    #[allow(dead_code)]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn now(&self) -> chrono::DateTime<chrono::Utc> {
+        self.clock.now()
+    }
+
+    /// Every metric family this service exports.
+    pub fn gather_metrics(&self) -> Vec<prometheus::proto::MetricFamily> {
+        self.registry.gather()
+    }
+
+    /// Flush state on the way out and report what was running.
+    pub async fn shutdown(&self) {
+        let corridors = self.corridors.write().await;
+        self.save_state(&corridors);
+        let active = corridors.values().filter(|c| c.status == CorridorStatus::Active).count();
+        tracing::info!("corrd stopped with {} corridors ({} active)", corridors.len(), active);
+    }
+
+    /// Refresh the fleet gauges and persist the corridor map if `CORRD_STATE_PATH` is
+    /// configured. Call after every mutation, with the write lock held.
+    fn save_state(&self, corridors: &HashMap<String, Corridor>) {
+        self.update_fleet_metrics(corridors.values());
+        if let Some(store) = &self.store {
+            let archived = self.archived.lock().unwrap();
+            if let Err(e) = store.save(corridors.values().chain(archived.values())) {
+                tracing::warn!("failed to persist corridor state: {}", e);
+            }
+        }
+    }
+
+    /// Move a corridor to `status`, crediting the time spent in the old one.
+    /// Leaving `Error` drops any acknowledgement.
+    fn set_status(&self, c: &mut Corridor, status: CorridorStatus) {
+        let now = self.now();
+        let dwell = (now - c.status_since).num_milliseconds().max(0) as f64 / 1000.0;
+        self.m_status_seconds.with_label_values(&[c.status.as_str()]).inc_by(dwell);
+        if matches!(c.status, CorridorStatus::Error) && !matches!(status, CorridorStatus::Error) {
+            c.acked = false;
+            c.ack = None;
+        }
+        if c.status != status {
+            let _ = self.status_events.send(StatusEvent {
+                corridor_id: c.id.clone(),
+                from: c.status.clone(),
+                to: status.clone(),
+                at: now,
+            });
+        }
+        c.status = status;
+        c.status_since = now;
+    }
+
+    /// Re-derive rate, power and eye margin from the lanes still up, and move an
+    /// `Active` corridor to `Error` (or back) across `max_down_lane_fraction`.
+    fn apply_lane_health(&self, c: &mut Corridor) {
+        let lanes = c.lanes.max(1);
+        let up = c.lanes_up().min(lanes);
+        c.achievable_gbps = (c.planned_gbps as u64 * up as u64 / lanes as u64) as u32;
+        c.total_power_mw = c.power_mw();
+        c.eye_margin_value = link::eye_margin(c.ber, c.achievable_gbps, c.min_gbps);
+        c.eye_margin = link::eye_margin_category(c.eye_margin_value).to_string();
+        let too_many_down = (lanes - up) as f64 / lanes as f64 > self.max_down_lane_fraction;
+        match c.status {
+            CorridorStatus::Active if too_many_down => self.set_status(c, CorridorStatus::Error),
+            CorridorStatus::Error if !too_many_down => self.set_status(c, CorridorStatus::Active),
+            _ => {}
+        }
+        c.receipt = Some(self.signer.sign(c));
+    }
+
+    async fn check_request(&self, req: &CorridorRequest) -> Result<()> {
+        self.check_shape(req)?;
+        self.check_attestation(req).await.map(|_| ())
+    }
+
+    /// Everything in `check_request` short of contacting attestd.
+    fn check_shape(&self, req: &CorridorRequest) -> Result<()> {
+        validate_request(req)?;
+        let max_reach = self.reach_limits.for_type(&req.corridor_type);
+        if req.reach_mm > max_reach {
+            return Err(anyhow::anyhow!(
+                "reach_mm {} exceeds the {} limit of {} mm",
+                req.reach_mm, req.corridor_type.as_str(), max_reach
+            ));
+        }
+        let min_latency = min_latency_ns(&req.corridor_type, req.reach_mm);
+        if (req.latency_budget_ns as f64) < min_latency {
+            return Err(anyhow::anyhow!(
+                "latency_budget_ns {} is below the {:.1} ns minimum for {} mm of {}",
+                req.latency_budget_ns, min_latency, req.reach_mm, req.corridor_type.as_str()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check the request's ticket when it needs one, returning the ticket's expiry.
+    async fn check_attestation(&self, req: &CorridorRequest) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+        if !req.attestation_required {
+            return Ok(None);
+        }
+        let ticket = req.attestation_ticket.as_deref().ok_or(CorridorError::AttestationRequired)?;
+        let verdict = match self.attest_cache.get(ticket, self.now()) {
+            Some(cached) => cached,
+            None => {
+                let verdict = self.verify_ticket(ticket).await.map_err(|e| CorridorError::Downstream(format!("attestd: {}", e)))?;
+                self.attest_cache.insert(ticket, verdict, self.now());
+                verdict
+            }
+        };
+        if !verdict.allows_at(self.now()) {
+            return Err(CorridorError::AttestationInvalid.into());
+        }
+        Ok(verdict.expires_at)
+    }
+
+    /// Verifiers are blocking, so the call runs on the blocking pool.
+    async fn verify_ticket(&self, ticket: &str) -> Result<Verdict> {
+        let (verifier, ticket) = (self.verifier.clone(), ticket.to_string());
+        let span = tracing::Span::current();
+        let started = std::time::Instant::now();
+        let verdict = tokio::task::spawn_blocking(move || {
+            let _entered = span.enter();
+            verifier.verify(&ticket)
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!(format!("join error: {}", e)))
+        .and_then(|verdict| verdict);
+        self.latency.record(Dependency::Attestd, started.elapsed());
+        verdict
+    }
+
+    pub async fn allocate_corridor(&self, req: CorridorRequest, created_by: &str) -> Result<Corridor> {
+        self.allocate_with_progress(req, created_by, &|_| {}).await
+    }
+
+    /// `allocate_corridor`, reporting each `AllocationStage` as it starts.
+    pub async fn allocate_with_progress(&self, req: CorridorRequest, created_by: &str, progress: Progress<'_>) -> Result<Corridor> {
+        let result = self.try_allocate(req, created_by, progress).await;
+        let outcome = if result.is_ok() { "success" } else { "rejected" };
+        self.m_allocate_requests.with_label_values(&[outcome]).inc();
+        result
+    }
+
+    /// `allocate_corridor` under an `Idempotency-Key`: repeating a key the caller
+    /// already used returns the corridor it created instead of allocating again.
+    pub async fn allocate_idempotent(
+        &self,
+        req: CorridorRequest,
+        created_by: &str,
+        key: &str,
+        progress: Progress<'_>,
+    ) -> Result<Corridor> {
+        match self.idempotency.claim(created_by, key) {
+            Claim::Replay(id) => self.get_corridor(&id).await.map_err(|_| {
+                CorridorError::Conflict(format!(
+                    "Idempotency-Key {} created corridor {}, which has since been deallocated",
+                    key, id
+                ))
+                .into()
+            }),
+            Claim::InFlight => Err(CorridorError::Conflict(format!(
+                "a request with Idempotency-Key {} is still in progress",
+                key
+            ))
+            .into()),
+            Claim::New(claim) => {
+                let result = self.allocate_with_progress(req, created_by, progress).await;
+                if let Ok(corridor) = &result {
+                    claim.complete(&corridor.id);
+                }
+                result
+            }
         }
     }
 
-    pub async fn allocate_corridor(&self, req: CorridorRequest) -> Result<Corridor> {
+    /// Everything allocation checks before touching state: request validity,
+    /// attestation, the link estimate and the admission hook.
+    async fn admit_request(&self, req: &CorridorRequest, created_by: &str, progress: Progress<'_>) -> Result<Admission> {
+        progress(AllocationStage::Validating);
+        self.check_shape(req)?;
         if req.attestation_required {
-            let ticket = req.attestation_ticket.clone().ok_or_else(|| anyhow::anyhow!("attestation required but no ticket provided"))?;
-            let ok = self.verify_attestation(&ticket)?;
-            if !ok {
-                return Err(anyhow::anyhow!("attestation ticket invalid or expired"));
+            progress(AllocationStage::Attesting);
+        }
+        let attestation_expires_at = self.check_attestation(req).await?;
+        progress(AllocationStage::Estimating);
+        let estimate = estimate_corridor(req)?;
+        self.hooks
+            .admit(&serde_json::json!({"created_by": created_by, "request": req, "estimate": &estimate}))
+            .await?;
+        Ok(Admission { estimate, attestation_expires_at })
+    }
+
+    /// Refuse `extra_mw` more draw on top of `used_mw` if it would take the fleet
+    /// past `CORRD_POWER_BUDGET_MW`.
+    fn check_power_budget(&self, used_mw: f64, extra_mw: f64) -> Result<()> {
+        let Some(budget) = self.power_budget_mw else { return Ok(()) };
+        if used_mw + extra_mw > budget {
+            return Err(CorridorError::InsufficientCapacity(format!(
+                "power budget exceeded: {:.1} mW requested, {:.1} of {:.1} mW in use",
+                extra_mw, used_mw, budget
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Refuse a request whose wavelengths are already held on its medium.
+    fn check_reservations(&self, req: &CorridorRequest) -> Result<()> {
+        let reservations = self.reservations.lock().unwrap();
+        for key in lambda_reservations(req.medium_id.as_deref(), &req.lambda_nm) {
+            if let Some(holder) = reservations.get(&key) {
+                return Err(CorridorError::Conflict(format!(
+                    "lambda_nm {} on medium {} is held by corridor {}",
+                    key.1, key.0, holder
+                ))
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    async fn try_allocate(&self, req: CorridorRequest, created_by: &str, progress: Progress<'_>) -> Result<Corridor> {
+        let admission = self.admit_request(&req, created_by, progress).await?;
+        let mut corridors = self.corridors.write().await;
+        self.check_power_budget(fleet_power_mw(corridors.values()), admission.estimate.power_mw)?;
+        self.check_reservations(&req)?;
+        let mut next_id = self.next_id.write().await;
+        let corridor = self.insert_corridor(&mut corridors, &mut next_id, req, admission, created_by);
+        self.save_state(&corridors);
+        self.update_lane_metrics(&corridor, None);
+        self.hooks.notify_allocated(&serde_json::json!({"type": "allocated", "corridor": &corridor}));
+        self.hooks.publish("allocated", &corridor, self.now());
+        Ok(corridor)
+    }
+
+    /// Allocate every request or none. All requests are admitted first; failures
+    /// (including wavelengths claimed twice within the batch) come back per index
+    /// and nothing is created. Otherwise all corridors are inserted under one
+    /// write lock.
+    pub async fn allocate_batch(
+        &self,
+        reqs: Vec<CorridorRequest>,
+        created_by: &str,
+    ) -> std::result::Result<Vec<Corridor>, BatchError> {
+        let mut conflicts: HashMap<usize, Vec<u32>> = HashMap::new();
+        for conflict in batch_wavelength_conflicts(&reqs) {
+            for index in conflict.indices {
+                conflicts.entry(index).or_default().push(conflict.lambda_nm);
+            }
+        }
+        let mut admissions = Vec::with_capacity(reqs.len());
+        let mut results = Vec::with_capacity(reqs.len());
+        for (index, req) in reqs.iter().enumerate() {
+            let admitted = match conflicts.get(&index) {
+                Some(lambdas) => Err(anyhow::anyhow!("lambda_nm {:?} also requested elsewhere in the batch", lambdas)),
+                None => self.admit_request(req, created_by, &|_| {}).await,
+            };
+            match admitted {
+                Ok(admission) => {
+                    results.push(BatchItemResult { index, ok: true, error: None, estimate: None });
+                    admissions.push(admission);
+                }
+                Err(e) => results.push(BatchItemResult { index, ok: false, error: Some(e.to_string()), estimate: None }),
             }
         }
+        if results.iter().any(|r| !r.ok) {
+            self.m_allocate_requests.with_label_values(&["rejected"]).inc_by(reqs.len() as u64);
+            return Err(BatchError::Items(results));
+        }
+
         let mut corridors = self.corridors.write().await;
+        if let Err(e) = self
+            .check_power_budget(fleet_power_mw(corridors.values()), admissions.iter().map(|a| a.estimate.power_mw).sum())
+            .and_then(|_| reqs.iter().try_for_each(|req| self.check_reservations(req)))
+        {
+            self.m_allocate_requests.with_label_values(&["rejected"]).inc_by(reqs.len() as u64);
+            return Err(BatchError::Rejected(e));
+        }
         let mut next_id = self.next_id.write().await;
+        let created: Vec<Corridor> = reqs
+            .into_iter()
+            .zip(admissions)
+            .map(|(req, admission)| self.insert_corridor(&mut corridors, &mut next_id, req, admission, created_by))
+            .collect();
+        self.save_state(&corridors);
+        drop(next_id);
+        drop(corridors);
+        self.m_allocate_requests.with_label_values(&["success"]).inc_by(created.len() as u64);
+        for corridor in &created {
+            self.update_lane_metrics(corridor, None);
+            self.hooks.notify_allocated(&serde_json::json!({"type": "allocated", "corridor": corridor}));
+            self.hooks.publish("allocated", corridor, self.now());
+        }
+        Ok(created)
+    }
 
-        let id = format!("cor-{:04x}", *next_id);
+    /// Build, sign and insert the corridor for an admitted request. The caller holds
+    /// both locks and persists afterwards.
+    fn insert_corridor(
+        &self,
+        corridors: &mut HashMap<String, Corridor>,
+        next_id: &mut u64,
+        req: CorridorRequest,
+        admission: Admission,
+        created_by: &str,
+    ) -> Corridor {
+        let Admission { estimate, attestation_expires_at } = admission;
+        // `{:04x}` is a minimum width, so ids keep growing past cor-ffff; skip any
+        // id already held, e.g. one restored from a state file written elsewhere.
+        let mut id = format!("cor-{:04x}", *next_id);
+        while corridors.contains_key(&id) {
+            *next_id += 1;
+            id = format!("cor-{:04x}", *next_id);
+        }
         *next_id += 1;
 
         // Simulate corridor allocation
-        let achievable_gbps = (req.min_gbps as f64 * 1.04) as u32; // 4% margin
-        let ber = 1.0e-12;
-        let eye_margin = if achievable_gbps >= req.min_gbps { "ok" } else { "marginal" };
+        let achievable_gbps = estimate.achievable_gbps;
+        let ber = estimate.ber;
+        let eye_margin_value = link::eye_margin(ber, achievable_gbps, req.min_gbps);
 
-        let corridor = Corridor {
+        let now = self.now();
+        let mut corridor = Corridor {
             id: id.clone(),
             corridor_type: req.corridor_type,
             lanes: req.lanes,
             lambda_nm: req.lambda_nm,
+            lambdas_per_lane: req.lambdas_per_lane,
             min_gbps: req.min_gbps,
             latency_budget_ns: req.latency_budget_ns,
             reach_mm: req.reach_mm,
@@ -179,38 +1887,483 @@ impl CorridorService {
             qos: req.qos,
             attestation_required: req.attestation_required,
             attestation_ticket: req.attestation_ticket,
+            attestation_expires_at,
+            optimize: req.optimize,
+            max_ber: req.max_ber,
+            medium_id: req.medium_id,
+            labels: req.labels,
+            modulation: estimate.modulation,
+            lane_rate_gbps: estimate.lane_rate_gbps,
+            fec: estimate.fec,
+            est_power_pj_per_bit: estimate.power_pj_per_bit,
+            total_power_mw: estimate.power_mw,
             achievable_gbps,
+            rate_margin: estimate.rate_margin,
             ber,
-            eye_margin: eye_margin.to_string(),
-            created_at: chrono::Utc::now(),
+            eye_margin: link::eye_margin_category(eye_margin_value).to_string(),
+            eye_margin_value,
+            created_at: now,
             status: CorridorStatus::Active,
+            status_since: now,
+            acked: false,
+            ack: None,
+            bootstrap: false,
+            bootstrap_id: None,
+            created_by: created_by.to_string(),
+            receipt: None,
+            last_recalibrated_at: None,
+            fault: None,
+            lane_status: vec![LaneState::Up; req.lanes as usize],
+            planned_gbps: achievable_gbps,
         };
+        corridor.receipt = Some(self.signer.sign(&corridor));
 
-        corridors.insert(id.clone(), corridor.clone());
-        self.update_lane_metrics(&corridor, None);
+        let mut reservations = self.reservations.lock().unwrap();
+        for key in lambda_reservations(corridor.medium_id.as_deref(), &corridor.lambda_nm) {
+            reservations.insert(key, id.clone());
+        }
+        drop(reservations);
+        corridors.insert(id, corridor.clone());
+        corridor
+    }
+
+    /// Allocate a new corridor from `id`'s request parameters, with each top-level
+    /// `CorridorRequest` field in `overrides` replacing the source's. The clone is
+    /// validated, attested and admitted like any other allocation, so a clone on the
+    /// same `medium_id` needs different `lambda_nm`.
+    pub async fn clone_corridor(
+        &self,
+        id: &str,
+        overrides: serde_json::Map<String, serde_json::Value>,
+        created_by: &str,
+    ) -> Result<Corridor> {
+        let source = self.get_corridor(id).await?;
+        let mut merged = serde_json::to_value(source.request())?;
+        if let Some(fields) = merged.as_object_mut() {
+            fields.extend(overrides);
+        }
+        let req: CorridorRequest = serde_path_to_error::deserialize(merged)
+            .map_err(|e| CorridorError::InvalidRequest(format!("override {}: {}", e.path(), e.inner())))?;
+        let corridor = self.allocate_corridor(req, created_by).await?;
+        tracing::info!("cloned {} from {}", corridor.id, id);
+        Ok(corridor)
+    }
+
+    /// Decommission a corridor: release its λs, drop its series and move it to the archive.
+    pub async fn deallocate_corridor(&self, id: &str) -> Result<Corridor> {
+        let mut corridors = self.corridors.write().await;
+        if corridors.get(id).is_some_and(|c| c.bootstrap) {
+            return Err(CorridorError::Conflict(format!(
+                "corridor {} comes from CORRD_BOOTSTRAP_CORRIDORS; remove its entry and restart to release it",
+                id
+            ))
+            .into());
+        }
+        let mut corridor = corridors.remove(id).ok_or_else(|| anyhow::Error::from(CorridorError::NotFound(id.to_string())))?;
+        self.set_status(&mut corridor, CorridorStatus::Decommissioned);
+        self.archived.lock().unwrap().insert(corridor.id.clone(), corridor.clone());
+        let mut reservations = self.reservations.lock().unwrap();
+        for key in lambda_reservations(corridor.medium_id.as_deref(), &corridor.lambda_nm) {
+            reservations.remove(&key);
+        }
+        drop(reservations);
+        self.save_state(&corridors);
+        let _ = self.m_uptime.remove_label_values(&[&corridor.id]);
+        self.recalibrations.lock().unwrap().remove(&corridor.id);
+        self.calibrations.lock().unwrap().remove(&corridor.id);
+        self.telemetry_samples.lock().unwrap().remove(&corridor.id);
+        let _ = self.m_power.remove_label_values(&[&corridor.id]);
+        let _ = self.m_labels.remove_label_values(&self.metric_label_values(&corridor));
+        for (cid, lane, lam) in lane_labels(&corridor) {
+            for gauge in self.lane_gauges() {
+                let _ = gauge.remove_label_values(&[&cid, &lane, &lam]);
+            }
+        }
+        self.hooks.publish("deallocated", &corridor, self.now());
         Ok(corridor)
     }
 
+    /// Apply a partial update. Changing `min_gbps` re-plans the link and re-derives
+    /// `achievable_gbps` and `eye_margin`; the receipt is re-signed.
+    pub async fn update_corridor(&self, id: &str, patch: CorridorPatch) -> Result<Corridor> {
+        let immutable = [
+            ("corridor_type", patch.corridor_type.is_some()),
+            ("lanes", patch.lanes.is_some()),
+            ("lambda_nm", patch.lambda_nm.is_some()),
+            ("lambdas_per_lane", patch.lambdas_per_lane.is_some()),
+        ];
+        if let Some((field, _)) = immutable.iter().find(|(_, set)| *set) {
+            return Err(anyhow::anyhow!("{} cannot be changed after allocation", field));
+        }
+        let mut corridors = self.corridors.write().await;
+        let others_mw = fleet_power_mw(corridors.values().filter(|o| o.id != id));
+        let c = corridors.get_mut(id).ok_or_else(|| anyhow::Error::from(CorridorError::NotFound(id.to_string())))?;
+        if c.status == CorridorStatus::Maintenance {
+            return Err(in_maintenance(id));
+        }
+        if let Some(min_gbps) = patch.min_gbps {
+            validate_request(&CorridorRequest { min_gbps, ..c.request() })?;
+            let penalty = link::channel_penalty(c.reach_mm, c.lanes, min_gbps, &c.lambda_nm);
+            let plan = plan_link(c.lanes, min_gbps, c.optimize, c.max_ber.map(|m| m / penalty))?;
+            let rate_margin = rate_margin(c.lanes, &c.corridor_type);
+            let achievable_gbps = estimate_achievable_gbps(min_gbps, rate_margin, &plan, &c.corridor_type);
+            let power_pj_per_bit = estimate_power_pj_per_bit(&plan, &c.corridor_type);
+            self.check_power_budget(others_mw, power_pj_per_bit * achievable_gbps as f64)?;
+            c.min_gbps = min_gbps;
+            c.modulation = plan.modulation;
+            c.lane_rate_gbps = plan.lane_rate_gbps;
+            c.fec = plan.fec;
+            c.est_power_pj_per_bit = power_pj_per_bit;
+            c.ber = (plan.est_ber * penalty).min(BER_CEILING);
+            c.planned_gbps = achievable_gbps;
+            c.rate_margin = rate_margin;
+            self.apply_lane_health(c);
+        }
+        if let Some(priority) = patch.priority {
+            c.qos.priority = priority;
+        }
+        if let Some(pfc) = patch.pfc {
+            c.qos.pfc = pfc;
+        }
+        c.receipt = Some(self.signer.sign(c));
+        let updated = c.clone();
+        self.save_state(&corridors);
+        Ok(updated)
+    }
+
+    /// Pre-create the corridors listed in the JSON file named by `CORRD_BOOTSTRAP_CORRIDORS`,
+    /// through the normal validation path. Returns how many were created.
+    ///
+    /// Each entry is keyed by its `bootstrap_id`, or `#<index>` in the file without
+    /// one; entries whose key a restored corridor already carries are skipped. A
+    /// restored bootstrap corridor whose entry is gone loses its `bootstrap` flag so
+    /// it can be deleted.
+    pub async fn bootstrap_from_env(&self) -> Result<usize> {
+        let Ok(path) = env::var("CORRD_BOOTSTRAP_CORRIDORS") else { return Ok(0) };
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("read bootstrap file {} failed: {}", path, e))?;
+        let entries: Vec<BootstrapEntry> = serde_json::from_str(&text)
+            .map_err(|e| anyhow::anyhow!("parse bootstrap file {} failed: {}", path, e))?;
+        let keyed: Vec<(String, CorridorRequest)> = entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, e)| (e.bootstrap_id.unwrap_or_else(|| format!("#{}", i)), e.request))
+            .collect();
+        let restored: HashSet<String> = {
+            let mut corridors = self.corridors.write().await;
+            // State written before `bootstrap_id` existed: bootstrap corridors were
+            // allocated in file order, so their ids give back the index.
+            let mut legacy: Vec<&mut Corridor> = corridors.values_mut().filter(|c| c.bootstrap && c.bootstrap_id.is_none()).collect();
+            legacy.sort_by(|a, b| a.id.cmp(&b.id));
+            for (i, c) in legacy.into_iter().enumerate() {
+                c.bootstrap_id = Some(format!("#{}", i));
+            }
+            for c in corridors.values_mut().filter(|c| c.bootstrap) {
+                if !keyed.iter().any(|(key, _)| c.bootstrap_id.as_ref() == Some(key)) {
+                    tracing::info!("bootstrap entry for {} is gone; it can now be deleted", c.id);
+                    c.bootstrap = false;
+                }
+            }
+            self.save_state(&corridors);
+            corridors.values().filter(|c| c.bootstrap).filter_map(|c| c.bootstrap_id.clone()).collect()
+        };
+        let mut created = 0;
+        for (key, req) in keyed {
+            if restored.contains(&key) {
+                continue;
+            }
+            let corridor = self
+                .allocate_corridor(req, "bootstrap")
+                .await
+                .map_err(|e| anyhow::anyhow!("bootstrap corridor {} rejected: {}", key, e))?;
+            let mut corridors = self.corridors.write().await;
+            if let Some(c) = corridors.get_mut(&corridor.id) {
+                c.bootstrap = true;
+                c.bootstrap_id = Some(key);
+            }
+            self.save_state(&corridors);
+            created += 1;
+        }
+        Ok(created)
+    }
+
+    /// Validate a batch of requests without allocating; optionally attach aggregate estimates.
+    pub async fn validate_batch(&self, reqs: &[CorridorRequest], with_estimate: bool) -> BatchValidation {
+        let mut checked: Vec<Result<CorridorEstimate>> = Vec::with_capacity(reqs.len());
+        for req in reqs {
+            checked.push(self.check_request(req).await.and_then(|_| estimate_corridor(req)));
+        }
+        let results: Vec<BatchItemResult> = checked
+            .iter()
+            .enumerate()
+            .map(|(index, r)| match r {
+                Ok(est) => BatchItemResult {
+                    index,
+                    ok: true,
+                    error: None,
+                    estimate: with_estimate.then(|| est.clone()),
+                },
+                Err(e) => BatchItemResult { index, ok: false, error: Some(e.to_string()), estimate: None },
+            })
+            .collect();
+
+        let estimate = with_estimate.then(|| {
+            let per_item: Vec<&CorridorEstimate> = checked.iter().filter_map(|r| r.as_ref().ok()).collect();
+            BatchEstimate {
+                total_power_mw: per_item.iter().map(|e| e.power_mw).sum(),
+                total_cost_units: per_item.iter().map(|e| e.cost_units).sum(),
+                total_lanes: per_item.iter().map(|e| e.lanes).sum(),
+                wavelength_conflicts: batch_wavelength_conflicts(reqs),
+            }
+        });
+
+        let valid = results.iter().all(|r| r.ok)
+            && estimate.as_ref().map(|e| e.wavelength_conflicts.is_empty()).unwrap_or(true);
+        BatchValidation { valid, results, estimate }
+    }
+
     pub async fn get_telemetry(&self, id: &str) -> Result<TelemetryData> {
         let corridors = self.corridors.read().await;
-        let _corridor = corridors.get(id)
-            .ok_or_else(|| anyhow::anyhow!("Corridor {} not found", id))?;
+        let corridor = corridors.get(id)
+            .ok_or_else(|| anyhow::Error::from(CorridorError::NotFound(id.to_string())))?;
 
-        // Simulate telemetry data
-        let data = TelemetryData {
-            ber: 1.1e-12,
+        // Simulate telemetry around the modelled BER, jittered by up to 5% per second
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        std::hash::Hash::hash(&(id, self.now().timestamp()), &mut hasher);
+        let jitter = (std::hash::Hasher::finish(&hasher) % 1001) as f64 / 1000.0 * 0.1 - 0.05;
+        let mut data = TelemetryData {
+            ber: (corridor.ber * (1.0 + jitter)).min(BER_CEILING),
             temp_c: 47.5,
-            power_pj_per_bit: 0.9,
+            power_pj_per_bit: corridor.est_power_pj_per_bit,
             drift: "low".to_string(),
             utilization_percent: 85.3,
             error_count: 0,
         };
+        if let Some(fault) = corridor.fault.as_ref().filter(|f| f.is_active(self.now())) {
+            fault.apply(&mut data);
+        }
         let corr = corridors.get(id).cloned();
         drop(corridors);
-        if let Some(c) = corr { self.update_lane_metrics(&c, Some(&data)); }
+        if let Some(c) = corr {
+            self.update_lane_metrics(&c, Some(&data));
+            self.alerts.evaluate(&c, &data, self.now());
+        }
+        self.record_telemetry(id, &data);
         Ok(data)
     }
 
+    fn record_telemetry(&self, id: &str, data: &TelemetryData) {
+        let mut all = self.telemetry_samples.lock().unwrap();
+        let window = all.entry(id.to_string()).or_default();
+        window.push_back((self.now(), data.clone()));
+        while window.len() > self.telemetry_window {
+            window.pop_front();
+        }
+    }
+
+    /// Min/max/avg/p95 of BER, temperature and utilization over the sample window.
+    /// A corridor with no samples yet is sampled once first.
+    pub async fn get_telemetry_summary(&self, id: &str) -> Result<TelemetrySummary> {
+        if !self.telemetry_samples.lock().unwrap().contains_key(id) {
+            self.get_telemetry(id).await?;
+        } else if !self.corridors.read().await.contains_key(id) {
+            return Err(anyhow::Error::from(CorridorError::NotFound(id.to_string())));
+        }
+        let all = self.telemetry_samples.lock().unwrap();
+        all.get(id)
+            .and_then(TelemetrySummary::over)
+            .ok_or_else(|| anyhow::Error::from(CorridorError::NotFound(id.to_string())))
+    }
+
+    /// Fleet totals from each Active corridor's most recent sample. Works from
+    /// borrows under the corridor read lock and the sample lock; nothing is cloned
+    /// and no new samples are taken.
+    pub async fn fleet_telemetry(&self) -> FleetTelemetry {
+        let corridors = self.corridors.read().await;
+        let samples = self.telemetry_samples.lock().unwrap();
+        let mut fleet = FleetTelemetry::default();
+        let (mut weighted_util, mut sampled_capacity) = (0.0, 0.0);
+        for c in corridors.values().filter(|c| c.status == CorridorStatus::Active) {
+            fleet.active_corridors += 1;
+            fleet.capacity_gbps += c.achievable_gbps as u64;
+            let Some((_, latest)) = samples.get(&c.id).and_then(|w| w.back()) else { continue };
+            fleet.sampled_corridors += 1;
+            weighted_util += latest.utilization_percent * c.achievable_gbps as f64;
+            sampled_capacity += c.achievable_gbps as f64;
+            if fleet.max_ber.is_none_or(|ber| latest.ber > ber) {
+                fleet.max_ber = Some(latest.ber);
+                fleet.max_ber_corridor = Some(c.id.clone());
+            }
+            fleet.max_temp_c = Some(fleet.max_temp_c.map_or(latest.temp_c, |t| t.max(latest.temp_c)));
+            fleet.total_error_count += latest.error_count;
+        }
+        if sampled_capacity > 0.0 {
+            fleet.utilization_percent = weighted_util / sampled_capacity;
+        }
+        fleet
+    }
+
+    /// Current telemetry broken out per wavelength.
+    pub async fn get_lane_telemetry(&self, id: &str) -> Result<Vec<LaneTelemetry>> {
+        let data = self.get_telemetry(id).await?;
+        let corridor = self.get_corridor(id).await?;
+        Ok(self.lane_telemetry(&corridor, Some(&data)))
+    }
+
+    /// Refresh `corridor_uptime_seconds`; called before each scrape since it moves continuously.
+    pub async fn update_uptime_metrics(&self) {
+        let corridors = self.corridors.read().await;
+        let now = self.now();
+        for c in corridors.values() {
+            self.m_uptime.with_label_values(&[&c.id]).set(c.uptime_seconds(now) as f64);
+        }
+    }
+
+    /// Prometheus text for only the series labelled with this corridor's id.
+    pub async fn corridor_metrics(&self, id: &str) -> Result<String> {
+        if !self.corridors.read().await.contains_key(id) {
+            return Err(anyhow::Error::from(CorridorError::NotFound(id.to_string())));
+        }
+        self.update_uptime_metrics().await;
+        let families: Vec<prometheus::proto::MetricFamily> = self.registry.gather()
+            .into_iter()
+            .filter_map(|mut family| {
+                let scoped: Vec<prometheus::proto::Metric> = family
+                    .take_metric()
+                    .into_iter()
+                    .filter(|m| m.get_label().iter().any(|l| l.get_name() == "corridor_id" && l.get_value() == id))
+                    .collect();
+                if scoped.is_empty() {
+                    return None;
+                }
+                family.set_metric(scoped.into());
+                Some(family)
+            })
+            .collect();
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&families, &mut buffer)?;
+        Ok(String::from_utf8_lossy(&buffer).to_string())
+    }
+
+    /// Reachability of HELIOPASS and attestd by TCP connect. Only attestd is required:
+    /// recalibration falls back to a synthetic result without HELIOPASS.
+    /// Returns whether all required dependencies answered, and the per-service report.
+    pub async fn dependency_health(&self) -> (bool, serde_json::Value) {
+        let timeout = std::time::Duration::from_millis(
+            env::var("CORRD_HEALTH_TIMEOUT_MS").ok().and_then(|v| v.parse().ok()).unwrap_or(500),
+        );
+        let attestd_url = attestation::attestd_url_from_env();
+        let checks = [(Dependency::Heliopass, self.heliopass_url.to_string(), false), (Dependency::Attestd, attestd_url, true)];
+        let mut healthy = true;
+        let mut services = serde_json::Map::new();
+        for (dep, url, required) in checks {
+            let result = http::probe(&url, timeout).await;
+            healthy &= result.is_ok() || !required;
+            services.insert(
+                dep.name().to_string(),
+                serde_json::json!({
+                    "url": url,
+                    "required": required,
+                    "reachable": result.is_ok(),
+                    "error": result.err().map(|e| e.to_string()),
+                }),
+            );
+        }
+        let status = if healthy { "ready" } else { "unavailable" };
+        (healthy, serde_json::json!({"status": status, "services": services}))
+    }
+
+    /// `GET /v1/selftest`: a dry-run allocation through validation and the link model,
+    /// the `dependency_health` probes (HELIOPASS optional, attestd required), and a
+    /// full encode of the metrics registry. Returns whether every required check
+    /// passed, and each check's outcome and duration.
+    pub async fn selftest(&self) -> (bool, serde_json::Value) {
+        let timeout = std::time::Duration::from_millis(
+            env::var("CORRD_HEALTH_TIMEOUT_MS").ok().and_then(|v| v.parse().ok()).unwrap_or(500),
+        );
+        let mut checks = Vec::new();
+        let mut record = |name: &str, required: bool, started: std::time::Instant, result: Result<()>| {
+            checks.push(serde_json::json!({
+                "name": name,
+                "required": required,
+                "passed": result.is_ok(),
+                "duration_ms": started.elapsed().as_secs_f64() * 1000.0,
+                "error": result.err().map(|e| e.to_string()),
+            }));
+        };
+
+        let started = std::time::Instant::now();
+        let probe = CorridorRequest {
+            corridor_type: CorridorType::SiCorridor,
+            lanes: 1,
+            lambda_nm: vec![1550],
+            lambdas_per_lane: None,
+            min_gbps: 40,
+            latency_budget_ns: 250,
+            reach_mm: 100,
+            mode: "selftest".to_string(),
+            qos: QoSSettings { pfc: false, priority: Priority::Low },
+            attestation_required: false,
+            attestation_ticket: None,
+            optimize: OptimizeFor::default(),
+            max_ber: None,
+            medium_id: None,
+            labels: HashMap::new(),
+        };
+        let dry_run = self.validate_batch(std::slice::from_ref(&probe), true).await;
+        let result = match dry_run.results.into_iter().next().and_then(|r| r.error) {
+            None => Ok(()),
+            Some(e) => Err(anyhow::anyhow!(e)),
+        };
+        record("dry_run_allocation", true, started, result);
+
+        let attestd_url = attestation::attestd_url_from_env();
+        for (dep, url, required) in [(Dependency::Heliopass, self.heliopass_url.to_string(), false), (Dependency::Attestd, attestd_url, true)] {
+            let started = std::time::Instant::now();
+            let result = http::probe(&url, timeout).await;
+            record(dep.name(), required, started, result);
+        }
+
+        let started = std::time::Instant::now();
+        let families = self.gather_metrics();
+        let result = TextEncoder::new().encode(&families, &mut Vec::new()).map_err(anyhow::Error::from);
+        record("metrics", true, started, result);
+
+        let passed = checks.iter().all(|c| c["passed"] == true || c["required"] == false);
+        let status = if passed { "pass" } else { "fail" };
+        (passed, serde_json::json!({"status": status, "checks": checks}))
+    }
+
+    /// Readiness based on rolling dependency latency: `degraded` once any p95 exceeds the threshold.
+    pub fn readiness(&self) -> serde_json::Value {
+        let heliopass = self.latency.summary(Dependency::Heliopass);
+        let attestd = self.latency.summary(Dependency::Attestd);
+        let status = if heliopass.degraded || attestd.degraded { "degraded" } else { "ready" };
+        serde_json::json!({
+            "status": status,
+            "dependencies": {
+                Dependency::Heliopass.name(): heliopass,
+                Dependency::Attestd.name(): attestd,
+            }
+        })
+    }
+
+    pub fn receipt_public_key(&self) -> String {
+        self.signer.public_key_hex()
+    }
+
+    pub fn subscribe_alerts(&self) -> tokio::sync::broadcast::Receiver<Alert> {
+        self.alerts.subscribe()
+    }
+
+    /// Status transitions of every corridor; callers filter by id. Fails if `id` is unknown.
+    pub async fn subscribe_status(&self, id: &str) -> Result<tokio::sync::broadcast::Receiver<StatusEvent>> {
+        if !self.corridors.read().await.contains_key(id) {
+            return Err(anyhow::Error::from(CorridorError::NotFound(id.to_string())));
+        }
+        Ok(self.status_events.subscribe())
+    }
+
     pub async fn recalibrate(&self, id: &str, req: RecalibrateRequest) -> Result<RecalibrateResponse> {
         // Acquire read lock to fetch current corridor
         let corridor_snapshot;
@@ -218,15 +2371,27 @@ impl CorridorService {
             let corridors = self.corridors.read().await;
             corridor_snapshot = corridors.get(id)
                 .cloned()
-                .ok_or_else(|| anyhow::anyhow!("Corridor {} not found", id))?;
+                .ok_or_else(|| anyhow::Error::from(CorridorError::NotFound(id.to_string())))?;
         }
 
+        // Held until we return, so only one calibration runs per corridor
+        let lock = self.calibrations.lock().unwrap().entry(id.to_string()).or_default().clone();
+        let _calibrating = lock
+            .try_lock_owned()
+            .map_err(|_| CorridorError::Conflict(format!("calibration in progress for corridor {}", id)))?;
+
         // Mark calibrating
         {
             let mut corridors = self.corridors.write().await;
             if let Some(c) = corridors.get_mut(id) {
-                c.status = CorridorStatus::Calibrating;
+                match c.status {
+                    CorridorStatus::Maintenance => return Err(in_maintenance(id)),
+                    CorridorStatus::AttestationExpired => return Err(attestation_expired(id)),
+                    _ => {}
+                }
+                self.set_status(c, CorridorStatus::Calibrating);
             }
+            self.save_state(&corridors);
         }
 
         // Gather basic telemetry for calibration inputs
@@ -240,7 +2405,7 @@ impl CorridorService {
         });
 
         // Build HELIOPASS request
-        #[derive(Debug, Serialize)]
+        #[derive(Debug, Clone, Serialize)]
         struct HelioCalibrationRequest {
             corridor_id: String,
             target_ber: f64,
@@ -277,210 +2442,944 @@ impl CorridorService {
             target_ber: req.target_ber,
             ambient_profile: req.ambient_profile,
             current_ber: telemetry.ber,
-            current_eye_margin: 0.8,
+            current_eye_margin: corridor_snapshot.eye_margin_value,
             temperature_c: telemetry.temp_c,
-            lambda_count: corridor_snapshot.lanes,
+            lambda_count: corridor_snapshot.lambda_nm.len() as u32,
+        };
+
+        let url = self.heliopass_url.join("/v1/heliopass/calibrate");
+        let timeout = self.heliopass_timeout;
+        let mut backoff = self.heliopass_backoff;
+        let mut attempt = 1;
+        let result = loop {
+            let (url, body) = (url.clone(), helio_req.clone());
+            let span = tracing::Span::current();
+            let started = std::time::Instant::now();
+            let result = tokio::task::spawn_blocking(move || -> Result<HelioCalibrationResponse> {
+                let _entered = span.enter();
+                http::post_json(&url, &body, timeout)
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!(format!("join error: {}", e)))?;
+            self.latency.record(Dependency::Heliopass, started.elapsed());
+            match result {
+                Err(e) if attempt < self.heliopass_attempts => {
+                    tracing::warn!("HELIOPASS attempt {}/{} failed, retrying in {:?}: {}", attempt, self.heliopass_attempts, backoff, e);
+                    tokio::time::sleep(backoff).await;
+                    backoff = backoff.saturating_mul(2).min(MAX_RETRY_BACKOFF);
+                    attempt += 1;
+                }
+                other => break other,
+            }
+        };
+
+        let out = match result {
+            Ok(h) => RecalibrateResponse {
+                status: h.status,
+                converged: h.converged,
+                bias_voltages: h.bias_voltages_mv,
+                lambda_shifts: h.lambda_shifts_nm,
+                laser_power_adjust: h.laser_power_adjust_db,
+                convergence_time_ms: h.convergence_time_ms,
+                final_ber: h.final_ber,
+                final_eye_margin: h.final_eye_margin,
+                power_savings: h.power_savings_percent,
+                source: CalibrationSource::Heliopass,
+            },
+            Err(e) => {
+                tracing::warn!("HELIOPASS unavailable after {} attempts, using synthetic calibration: {}", attempt, e);
+                synthetic_calibration(&corridor_snapshot, req.target_ber)
+            }
+        };
+
+        // Mark active again
+        {
+            let mut corridors = self.corridors.write().await;
+            if let Some(c) = corridors.get_mut(id) {
+                let now = self.now();
+                self.set_status(c, CorridorStatus::Active);
+                self.apply_lane_health(c);
+                c.last_recalibrated_at = Some(now);
+                self.record_recalibration(id, RecalibrationRecord { at: now, result: out.clone() });
+            }
+            self.save_state(&corridors);
+        }
+
+        Ok(out)
+    }
+
+    /// Append to the corridor's history, dropping the oldest entries past the cap.
+    fn record_recalibration(&self, id: &str, record: RecalibrationRecord) {
+        let mut all = self.recalibrations.lock().unwrap();
+        let history = all.entry(id.to_string()).or_default();
+        history.push_back(record);
+        while history.len() > self.recalibration_history {
+            history.pop_front();
+        }
+    }
+
+    /// Recent recalibrations of a corridor, oldest first.
+    pub async fn recalibration_history(&self, id: &str) -> Result<Vec<RecalibrationRecord>> {
+        if !self.corridors.read().await.contains_key(id) {
+            return Err(anyhow::Error::from(CorridorError::NotFound(id.to_string())));
+        }
+        let all = self.recalibrations.lock().unwrap();
+        Ok(all.get(id).map(|h| h.iter().cloned().collect()).unwrap_or_default())
+    }
+
+    /// PRNG for one lane's simulated variation at one second of corridor age. The
+    /// same seed, corridor, lane and age always give the same values.
+    fn lane_rng(&self, corridor_id: &str, index: usize, tick: i64) -> rand::rngs::StdRng {
+        use rand::SeedableRng;
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        std::hash::Hash::hash(&(self.sim_seed, corridor_id, index, tick), &mut hasher);
+        rand::rngs::StdRng::seed_from_u64(std::hash::Hasher::finish(&hasher))
+    }
+
+    /// Per-wavelength values around the corridor-level sample `telem`; the lane
+    /// gauges and `GET /v1/corridors/{id}/telemetry/lanes` both come from here.
+    fn lane_telemetry(&self, corridor: &Corridor, telem: Option<&TelemetryData>) -> Vec<LaneTelemetry> {
+        use rand::Rng;
+        let ber = telem.map(|t| t.ber).unwrap_or(1.0e-12);
+        let temp = telem.map(|t| t.temp_c).unwrap_or(40.0);
+        let power = telem.map(|t| t.power_pj_per_bit).unwrap_or(corridor.est_power_pj_per_bit);
+        let util = telem.map(|t| t.utilization_percent).unwrap_or(0.0);
+        let errs = telem.map(|t| t.error_count).unwrap_or(0);
+        let power_step = match corridor.corridor_type {
+            CorridorType::SiCorridor => SI_LANE_POWER_STEP,
+            CorridorType::CarbonCorridor => CARBON_LANE_POWER_STEP,
+        };
+        // Corridor age rather than wall time, so a fixed seed replays the same series.
+        let tick = corridor.uptime_seconds(self.now());
+        corridor
+            .lambda_nm
+            .iter()
+            .enumerate()
+            .map(|(i, lambda)| {
+                let mut rng = self.lane_rng(&corridor.id, i, tick);
+                let lane = corridor.lane_of(i);
+                let state = corridor.lane_state(lane);
+                let lane_ber = ber * (1.0 + rng.gen_range(-LANE_BER_SPREAD..=LANE_BER_SPREAD));
+                LaneTelemetry {
+                    lane,
+                    lambda_nm: *lambda,
+                    state,
+                    ber: match state {
+                        LaneState::Up => lane_ber,
+                        LaneState::Degraded => lane_ber * DEGRADED_LANE_BER_FACTOR,
+                        LaneState::Down => BER_CEILING,
+                    }
+                    .min(BER_CEILING),
+                    temp_c: temp + rng.gen_range(-LANE_TEMP_SPREAD_C..=LANE_TEMP_SPREAD_C),
+                    power_pj_per_bit: power + rng.gen_range(-power_step..=power_step),
+                    utilization_percent: if state == LaneState::Down { 0.0 } else { util },
+                    error_count: errs,
+                }
+            })
+            .collect()
+    }
+
+    fn update_lane_metrics(&self, corridor: &Corridor, telem: Option<&TelemetryData>) {
+        for l in self.lane_telemetry(corridor, telem) {
+            let lane = l.lane.to_string();
+            let lam = l.lambda_nm.to_string();
+            self.m_lane_ber.with_label_values(&[&corridor.id, &lane, &lam]).set(l.ber);
+            self.m_lane_eye
+                .with_label_values(&[&corridor.id, &lane, &lam])
+                .set(link::eye_margin(l.ber, corridor.achievable_gbps, corridor.min_gbps));
+            self.m_lane_temp.with_label_values(&[&corridor.id, &lane, &lam]).set(l.temp_c);
+            self.m_lane_power.with_label_values(&[&corridor.id, &lane, &lam]).set(l.power_pj_per_bit);
+            self.m_lane_util.with_label_values(&[&corridor.id, &lane, &lam]).set(l.utilization_percent);
+            self.m_lane_err.with_label_values(&[&corridor.id, &lane, &lam]).set(l.error_count as f64);
+            let up = if l.state == LaneState::Down { 0.0 } else { 1.0 };
+            self.m_lane_up.with_label_values(&[&corridor.id, &lane, &lam]).set(up);
+        }
+    }
+
+    fn update_fleet_metrics<'a>(&self, corridors: impl IntoIterator<Item = &'a Corridor> + Clone) {
+        self.m_corridor_total.set(corridors.clone().into_iter().count() as i64);
+        for c in corridors.clone() {
+            self.m_power.with_label_values(&[&c.id]).set(c.total_power_mw);
+            self.m_labels.with_label_values(&self.metric_label_values(c)).set(1);
+        }
+        for status in [
+            CorridorStatus::Active,
+            CorridorStatus::Calibrating,
+            CorridorStatus::Error,
+            CorridorStatus::Maintenance,
+            CorridorStatus::AttestationExpired,
+        ] {
+            let count = corridors.clone().into_iter().filter(|c| c.status == status).count();
+            self.m_corridor_status.with_label_values(&[status.as_str()]).set(count as i64);
+            if status == CorridorStatus::AttestationExpired {
+                self.m_attestation_expired.set(count as i64);
+            }
+        }
+    }
+
+    /// `corridor_labels` values for `c`: its id, then each exported key ("" if unset).
+    fn metric_label_values<'a>(&'a self, c: &'a Corridor) -> Vec<&'a str> {
+        std::iter::once(c.id.as_str())
+            .chain(self.metric_label_keys.iter().map(|k| c.labels.get(k).map(String::as_str).unwrap_or_default()))
+            .collect()
+    }
+
+    fn lane_gauges(&self) -> [&GaugeVec; 7] {
+        [
+            &self.m_lane_ber,
+            &self.m_lane_temp,
+            &self.m_lane_power,
+            &self.m_lane_util,
+            &self.m_lane_err,
+            &self.m_lane_eye,
+            &self.m_lane_up,
+        ]
+    }
+
+    /// Drop lane series with no matching corridor and re-emit series missing for
+    /// known corridors. Returns the number of fixes applied.
+    pub async fn reconcile_metrics(&self) -> u64 {
+        let corridors = self.corridors.read().await;
+        let expected: HashSet<LaneLabels> = corridors.values().flat_map(lane_labels).collect();
+        let mut fixes = 0;
+        let mut stale: HashSet<String> = HashSet::new();
+        for gauge in self.lane_gauges() {
+            let present = series_labels(gauge);
+            for (id, lane, lam) in present.difference(&expected) {
+                tracing::warn!("reconcile: removing orphaned series corridor_id={} lane={} lambda_nm={}", id, lane, lam);
+                let _ = gauge.remove_label_values(&[id, lane, lam]);
+                fixes += 1;
+            }
+            stale.extend(expected.difference(&present).map(|(id, _, _)| id.clone()));
+        }
+        for id in stale {
+            if let Some(c) = corridors.get(&id) {
+                tracing::warn!("reconcile: re-emitting missing lane series for {}", id);
+                self.update_lane_metrics(c, None);
+                fixes += 1;
+            }
+        }
+        self.m_reconcile_fixes.inc_by(fixes);
+        fixes
+    }
+
+    /// Re-check the ticket of every Active or Error corridor whose attestation has
+    /// passed `attestation_expires_at`. A renewed ticket just moves the expiry;
+    /// otherwise (including attestd being unreachable) the corridor goes to
+    /// `AttestationExpired`. An expired corridor whose ticket is later renewed returns
+    /// to Active. Returns how many corridors changed status.
+    pub async fn check_attestation_expiry(&self) -> usize {
+        let now = self.now();
+        let due: Vec<(String, String)> = {
+            let corridors = self.corridors.read().await;
+            corridors
+                .values()
+                .filter(|c| c.attestation_expires_at.is_some_and(|t| t <= now))
+                .filter(|c| matches!(c.status, CorridorStatus::Active | CorridorStatus::Error | CorridorStatus::AttestationExpired))
+                .filter_map(|c| Some((c.id.clone(), c.attestation_ticket.clone()?)))
+                .collect()
+        };
+        if due.is_empty() {
+            return 0;
+        }
+        // attestd is called without the lock held; corridors removed meanwhile are skipped.
+        let mut verdicts: Vec<(String, Result<Verdict>)> = Vec::with_capacity(due.len());
+        for (id, ticket) in due {
+            let verdict = self.verify_ticket(&ticket).await;
+            if let Ok(v) = &verdict {
+                self.attest_cache.insert(&ticket, *v, self.now());
+            }
+            verdicts.push((id, verdict));
+        }
+        let mut corridors = self.corridors.write().await;
+        let mut changed = 0;
+        for (id, verdict) in verdicts {
+            let Some(c) = corridors.get_mut(&id) else { continue };
+            match verdict {
+                Ok(v) if v.allows_at(now) => {
+                    c.attestation_expires_at = v.expires_at;
+                    if c.status == CorridorStatus::AttestationExpired {
+                        self.set_status(c, CorridorStatus::Active);
+                        self.apply_lane_health(c);
+                        tracing::info!("corridor {} attestation renewed, back to {:?}", id, c.status);
+                        changed += 1;
+                    }
+                }
+                _ if c.status == CorridorStatus::AttestationExpired => {}
+                verdict => {
+                    if let Err(e) = verdict {
+                        tracing::warn!("could not re-check attestation for corridor {}: {:#}", id, e);
+                    }
+                    self.set_status(c, CorridorStatus::AttestationExpired);
+                    let expired_at = c.attestation_expires_at.map(|t| t.to_rfc3339()).unwrap_or_default();
+                    tracing::warn!("corridor {} attestation expired at {}", id, expired_at);
+                    self.hooks.publish("attestation_expired", c, self.now());
+                    changed += 1;
+                }
+            }
+        }
+        self.save_state(&corridors);
+        changed
+    }
+
+    pub fn capabilities(&self) -> Capabilities {
+        let points = link::operating_points();
+        let mut modulation_formats: Vec<Modulation> = Vec::new();
+        for (m, _) in &points {
+            if !modulation_formats.contains(m) {
+                modulation_formats.push(*m);
+            }
+        }
+        let types = vec![CorridorType::SiCorridor, CorridorType::CarbonCorridor];
+        let max_reach_mm = types
+            .iter()
+            .map(|t| (t.as_str().to_string(), self.reach_limits.for_type(t)))
+            .collect();
+        Capabilities {
+            corridor_types: types,
+            modulation_formats,
+            lane_rates_gbps: points.iter().map(|(_, g)| *g).collect(),
+            bands: Band::ALL.to_vec(),
+            optimize: vec![OptimizeFor::Throughput, OptimizeFor::Power, OptimizeFor::Balanced],
+            limits: CapabilityLimits { max_reach_mm },
+        }
+    }
+
+    /// Acknowledge a corridor in `Error` so repeat alerts stay quiet until it recovers.
+    pub async fn acknowledge(&self, id: &str, by: &str, req: AckRequest) -> Result<Corridor> {
+        let mut corridors = self.corridors.write().await;
+        let c = corridors.get_mut(id).ok_or_else(|| anyhow::Error::from(CorridorError::NotFound(id.to_string())))?;
+        if !matches!(c.status, CorridorStatus::Error) {
+            return Err(CorridorError::Conflict(format!(
+                "corridor {} is {}, only {} corridors can be acknowledged",
+                id, c.status.as_str(), CorridorStatus::Error.as_str()
+            ))
+            .into());
+        }
+        c.acked = true;
+        c.ack = Some(Acknowledgement { by: by.to_string(), at: self.now(), note: req.note });
+        let acked = c.clone();
+        self.save_state(&corridors);
+        Ok(acked)
+    }
+
+    /// Take a corridor out of service for physical work. Recalibration and updates
+    /// are refused with 409 until `resume`.
+    pub async fn enter_maintenance(&self, id: &str) -> Result<Corridor> {
+        let mut corridors = self.corridors.write().await;
+        let c = corridors.get_mut(id).ok_or_else(|| anyhow::Error::from(CorridorError::NotFound(id.to_string())))?;
+        match c.status {
+            CorridorStatus::Maintenance => {
+                return Err(CorridorError::Conflict(format!("corridor {} is already {}", id, CorridorStatus::Maintenance.as_str())).into())
+            }
+            CorridorStatus::Calibrating => {
+                return Err(CorridorError::Conflict(format!("corridor {} is {}, retry once it finishes", id, CorridorStatus::Calibrating.as_str())).into())
+            }
+            CorridorStatus::AttestationExpired => return Err(attestation_expired(id)),
+            CorridorStatus::Active | CorridorStatus::Error | CorridorStatus::Decommissioned => {}
+        }
+        self.set_status(c, CorridorStatus::Maintenance);
+        let drained = c.clone();
+        self.save_state(&corridors);
+        tracing::info!("corridor {} entered Maintenance", id);
+        Ok(drained)
+    }
+
+    /// Return a corridor from Maintenance to Active.
+    pub async fn resume(&self, id: &str) -> Result<Corridor> {
+        let mut corridors = self.corridors.write().await;
+        let c = corridors.get_mut(id).ok_or_else(|| anyhow::Error::from(CorridorError::NotFound(id.to_string())))?;
+        if c.status != CorridorStatus::Maintenance {
+            return Err(CorridorError::Conflict(format!("corridor {} is {}, not {}", id, c.status.as_str(), CorridorStatus::Maintenance.as_str())).into());
+        }
+        self.set_status(c, CorridorStatus::Active);
+        self.apply_lane_health(c);
+        let resumed = c.clone();
+        self.save_state(&corridors);
+        tracing::info!("corridor {} resumed from Maintenance", id);
+        Ok(resumed)
+    }
+
+    /// Make telemetry report `req`'s values for `req.duration_ms`, replacing any
+    /// earlier fault, and/or change one lane's state. Lane gauges and alerts are
+    /// refreshed straight away.
+    pub async fn inject_fault(&self, id: &str, req: FaultRequest) -> Result<Corridor> {
+        let timed = req.lane.is_none() || req.overrides_telemetry();
+        if timed && (req.duration_ms == 0 || req.duration_ms > MAX_FAULT_DURATION_MS) {
+            return Err(anyhow::anyhow!("duration_ms must be between 1 and {}", MAX_FAULT_DURATION_MS));
+        }
+        if let Some(ber) = req.ber {
+            if !(ber > 0.0 && ber <= BER_CEILING) {
+                return Err(anyhow::anyhow!("ber must be in (0, {}]", BER_CEILING));
+            }
+        }
+        if req.temp_c.is_some_and(|t| !t.is_finite()) {
+            return Err(anyhow::anyhow!("temp_c must be finite"));
+        }
+        let faulted = {
+            let mut corridors = self.corridors.write().await;
+            let c = corridors.get_mut(id).ok_or_else(|| anyhow::Error::from(CorridorError::NotFound(id.to_string())))?;
+            if let Some(lane) = req.lane {
+                if lane == 0 || lane > c.lane_status.len() {
+                    return Err(anyhow::anyhow!("lane must be between 1 and {}", c.lane_status.len()));
+                }
+            }
+            if timed {
+                c.fault = Some(InjectedFault {
+                    ber: req.ber,
+                    temp_c: req.temp_c,
+                    error_count: req.error_count,
+                    until: self.now() + chrono::Duration::milliseconds(req.duration_ms as i64),
+                });
+                tracing::warn!("injected fault on {} for {} ms", id, req.duration_ms);
+            }
+            if let Some(lane) = req.lane {
+                let state = req.lane_state.unwrap_or(LaneState::Down);
+                c.lane_status[lane - 1] = state;
+                self.apply_lane_health(c);
+                tracing::warn!("lane {} of {} set to {:?}", lane, id, state);
+            }
+            let faulted = c.clone();
+            self.save_state(&corridors);
+            faulted
+        };
+        self.get_telemetry(id).await?;
+        Ok(faulted)
+    }
+
+    /// Ramp simulated load on an Active corridor up to `min_gbps`. Each step is
+    /// recorded as a telemetry sample and pushed to the lane gauges and alert engine
+    /// like a live one, and an injected fault overrides the modelled values as usual.
+    /// The run stops with an error if the corridor leaves Active or is deleted.
+    pub async fn benchmark(&self, id: &str, req: BenchmarkRequest) -> Result<BenchmarkReport> {
+        let steps = req.steps.unwrap_or(DEFAULT_BENCHMARK_STEPS);
+        let step_ms = req.step_ms.unwrap_or(DEFAULT_BENCHMARK_STEP_MS);
+        if steps == 0 || steps > MAX_BENCHMARK_STEPS {
+            return Err(anyhow::anyhow!("steps must be between 1 and {}", MAX_BENCHMARK_STEPS));
+        }
+        if step_ms > MAX_BENCHMARK_STEP_MS {
+            return Err(anyhow::anyhow!("step_ms must be at most {}", MAX_BENCHMARK_STEP_MS));
+        }
+        let started = std::time::Instant::now();
+        let mut results: Vec<BenchmarkStep> = Vec::with_capacity(steps as usize);
+        for step in 1..=steps {
+            if step > 1 {
+                tokio::time::sleep(std::time::Duration::from_millis(step_ms)).await;
+            }
+            let c = self.get_corridor(id).await?;
+            match c.status {
+                CorridorStatus::Active => {}
+                CorridorStatus::Maintenance => return Err(in_maintenance(id)),
+                other => {
+                    return Err(CorridorError::Conflict(format!("corridor {} is {}; benchmarks need an {} corridor", id, other.as_str(), CorridorStatus::Active.as_str())).into())
+                }
+            }
+            let offered = (c.min_gbps as u64 * step as u64 / steps as u64) as u32;
+            let sustained = offered.min(c.achievable_gbps);
+            let ber = ber_at_load(&c, offered);
+            let mut data = TelemetryData {
+                ber,
+                temp_c: 47.5,
+                power_pj_per_bit: c.est_power_pj_per_bit,
+                drift: "low".to_string(),
+                utilization_percent: if c.achievable_gbps == 0 { 0.0 } else { sustained as f64 / c.achievable_gbps as f64 * 100.0 },
+                error_count: (ber * sustained as f64 * 1.0e9 * step_ms as f64 / 1000.0).round() as u64,
+            };
+            if let Some(fault) = c.fault.as_ref().filter(|f| f.is_active(self.now())) {
+                fault.apply(&mut data);
+            }
+            self.update_lane_metrics(&c, Some(&data));
+            self.alerts.evaluate(&c, &data, self.now());
+            self.record_telemetry(id, &data);
+            results.push(BenchmarkStep {
+                offered_gbps: offered,
+                sustained_gbps: sustained,
+                ber: data.ber,
+                eye_margin: link::eye_margin(data.ber, c.achievable_gbps, offered),
+            });
+        }
+        let held = |s: &&BenchmarkStep| s.eye_margin >= link::EYE_MARGIN_OK;
+        let initial_ber = results[0].ber;
+        let peak_ber = results.iter().map(|s| s.ber).fold(0.0, f64::max);
+        let min_eye_margin = results.iter().map(|s| s.eye_margin).fold(f64::INFINITY, f64::min);
+        let report = BenchmarkReport {
+            corridor_id: id.to_string(),
+            sustained_gbps: results.iter().filter(held).map(|s| s.sustained_gbps).max().unwrap_or(0),
+            initial_ber,
+            peak_ber,
+            ber_escalation: if initial_ber > 0.0 { peak_ber / initial_ber } else { 1.0 },
+            min_eye_margin,
+            eye_margin_held: results.iter().all(|s| held(&s)),
+            duration_ms: started.elapsed().as_millis() as u64,
+            steps: results,
         };
+        tracing::info!(
+            "benchmark of {}: {} Gb/s sustained, BER x{:.2}, eye margin {}",
+            id,
+            report.sustained_gbps,
+            report.ber_escalation,
+            if report.eye_margin_held { "held" } else { "dropped below 0.3" }
+        );
+        Ok(report)
+    }
+
+    /// Grid capacity left in a band given the wavelengths already allocated.
+    pub async fn topology_capacity(&self, q: &CapacityQuery) -> Result<CapacityReport> {
+        if !q.spacing_ghz.is_finite() || q.spacing_ghz <= 0.0 || q.lanes == 0 {
+            return Err(anyhow::anyhow!("spacing_ghz and lanes must both be positive"));
+        }
+        let corridors = self.corridors.read().await;
+        let allocator = LambdaAllocator::from_plans(corridors.values().map(|c| c.lambda_nm.as_slice()));
+        Ok(allocator.capacity(q.band, q.spacing_ghz, q.lanes))
+    }
+
+    pub async fn list_corridors(&self, query: &ListQuery) -> CorridorPage<Corridor> {
+        let corridors = self.corridors.read().await;
+        let page = query.page(corridors.values(), DEFAULT_LIST_LIMIT);
+        CorridorPage { items: page.items.into_iter().cloned().collect(), total: page.total, offset: page.offset }
+    }
+
+    /// Ids for streaming; unlike the JSON list there is no default page size.
+    pub async fn corridor_ids(&self, query: &ListQuery) -> Vec<String> {
+        let corridors = self.corridors.read().await;
+        query.page(corridors.values(), usize::MAX).items.into_iter().map(|c| c.id.clone()).collect()
+    }
+
+    pub async fn get_corridor(&self, id: &str) -> Result<Corridor> {
+        let corridors = self.corridors.read().await;
+        corridors.get(id)
+            .cloned()
+            .ok_or_else(|| anyhow::Error::from(CorridorError::NotFound(id.to_string())))
+    }
 
-        // Call HELIOPASS service without adding new crates.
-        // Minimal HTTP client implemented over std::net::TcpStream.
-        let base = self.heliopass_url.trim_end_matches('/').to_string();
-        let helio_path = "/v1/heliopass/calibrate".to_string();
-        let payload = serde_json::to_vec(&helio_req)?;
+    /// Like `get_corridor`, falling back to the archive.
+    pub async fn get_corridor_or_archived(&self, id: &str) -> Result<Corridor> {
+        let corridors = self.corridors.read().await;
+        corridors.get(id)
+            .or(self.archived.lock().unwrap().get(id))
+            .cloned()
+            .ok_or_else(|| anyhow::Error::from(CorridorError::NotFound(id.to_string())))
+    }
 
-        let result = tokio::task::spawn_blocking(move || -> Result<HelioCalibrationResponse> {
-            // Parse base URL: support forms like http://host:port or host:port
-            let mut host_port = base.clone();
-            if let Some(stripped) = host_port.strip_prefix("http://") {
-                host_port = stripped.to_string();
-            }
-            if let Some(stripped) = host_port.strip_prefix("https://") {
-                // HTTPS not supported in this minimal client
-                host_port = stripped.to_string();
-            }
-            // Remove any path suffix on base
-            if let Some((hp, _)) = host_port.split_once('/') {
-                host_port = hp.to_string();
-            }
+    /// Archived corridors, most recently decommissioned first.
+    pub async fn archived_corridors(&self) -> Vec<Corridor> {
+        let _corridors = self.corridors.read().await;
+        let mut list: Vec<Corridor> = self.archived.lock().unwrap().values().cloned().collect();
+        list.sort_by(|a, b| b.status_since.cmp(&a.status_since).then_with(|| a.id.cmp(&b.id)));
+        list
+    }
 
-            // Default port if not specified
-            let addr = if host_port.contains(':') { host_port.clone() } else { format!("{}:{}", host_port, 80) };
+    /// Drop an archived corridor for good.
+    pub async fn purge_archived(&self, id: &str) -> Result<Corridor> {
+        let corridors = self.corridors.write().await;
+        let corridor = self.archived.lock().unwrap().remove(id)
+            .ok_or_else(|| anyhow::Error::from(CorridorError::NotFound(id.to_string())))?;
+        self.save_state(&corridors);
+        self.hooks.publish("purged", &corridor, self.now());
+        Ok(corridor)
+    }
+}
 
-            let mut stream = TcpStream::connect(addr.clone())
-                .map_err(|e| anyhow::anyhow!(format!("connect {} failed: {}", addr, e)))?;
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
 
-            // Compose HTTP/1.1 request
-            let host_header = host_port;
-            let request = format!(
-                "POST {path} HTTP/1.1\r\nHost: {host}\r\nContent-Type: application/json\r\nConnection: close\r\nContent-Length: {len}\r\n\r\n",
-                path = helio_path,
-                host = host_header,
-                len = payload.len()
-            );
+/// Stream the matching corridors as one JSON line each, taking the read lock only per item.
+fn ndjson_corridor_stream(
+    service: Arc<CorridorService>,
+    query: ListQuery,
+    fields: Option<Vec<String>>,
+) -> impl futures_util::Stream<Item = Result<Vec<u8>, std::convert::Infallible>> {
+    futures_util::stream::once(async move {
+        let ids = service.corridor_ids(&query).await;
+        futures_util::stream::iter(ids).filter_map(move |id| {
+            let service = service.clone();
+            let fields = fields.clone();
+            async move {
+                // Corridors removed since the id snapshot are skipped.
+                let corridor = service.get_corridor(&id).await.ok()?;
+                let mut line = serde_json::to_vec(&project_corridor(&corridor, fields.as_deref(), service.now())).ok()?;
+                line.push(b'\n');
+                Some(Ok(line))
+            }
+        })
+    })
+    .flatten()
+}
 
-            stream.write_all(request.as_bytes())
-                .map_err(|e| anyhow::anyhow!(format!("write header failed: {}", e)))?;
-            stream.write_all(&payload)
-                .map_err(|e| anyhow::anyhow!(format!("write body failed: {}", e)))?;
-            stream.flush().ok();
-
-            let mut buf = Vec::new();
-            stream.read_to_end(&mut buf).ok();
-            let resp = String::from_utf8_lossy(&buf);
-
-            // Separate headers and body
-            let parts: Vec<&str> = resp.split("\r\n\r\n").collect();
-            if parts.len() < 2 {
-                return Err(anyhow::anyhow!("invalid HTTP response"));
-            }
-            // Parse status code quickly
-            if let Some(status_line) = resp.lines().next() {
-                if !(status_line.contains("200") || status_line.contains("201")) {
-                    return Err(anyhow::anyhow!(format!("HELIOPASS HTTP error: {}", status_line)));
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TelemetryWsQuery {
+    pub interval_ms: Option<u64>,
+}
+
+/// Push a telemetry frame every `interval` until the client leaves or the corridor
+/// disappears, in which case the socket is closed with a close frame.
+async fn stream_telemetry(socket: warp::ws::WebSocket, service: Arc<CorridorService>, id: String, interval: std::time::Duration) {
+    let (mut tx, mut incoming) = socket.split();
+    let mut tick = tokio::time::interval(interval);
+    loop {
+        tokio::select! {
+            msg = incoming.next() => match msg {
+                Some(Ok(m)) if !m.is_close() => continue,
+                _ => break,
+            },
+            _ = tick.tick() => match service.get_telemetry(&id).await {
+                Ok(data) => {
+                    let frame = serde_json::to_string(&data).unwrap_or_default();
+                    if tx.send(warp::ws::Message::text(frame)).await.is_err() {
+                        break;
+                    }
                 }
-            }
-            let body = parts[parts.len() - 1];
-            let parsed: HelioCalibrationResponse = serde_json::from_str(body)
-                .map_err(|e| anyhow::anyhow!(format!("parse JSON failed: {}", e)))?;
-            Ok(parsed)
-        }).await
-        .map_err(|e| anyhow::anyhow!(format!("join error: {}", e)))?;
+                Err(_) => {
+                    let _ = tx.send(warp::ws::Message::close_with(1000u16, "corridor deleted")).await;
+                    break;
+                }
+            },
+        }
+    }
+    let _ = tx.close().await;
+}
 
-        let out = match result {
-            Ok(h) => RecalibrateResponse {
-                status: h.status,
-                converged: h.converged,
-                bias_voltages: h.bias_voltages_mv,
-                lambda_shifts: h.lambda_shifts_nm,
-                laser_power_adjust: h.laser_power_adjust_db,
-                convergence_time_ms: h.convergence_time_ms,
-                final_ber: h.final_ber,
-                final_eye_margin: h.final_eye_margin,
-                power_savings: h.power_savings_percent,
+/// Forward fleet alerts to a websocket client until either side goes away.
+async fn stream_alerts(socket: warp::ws::WebSocket, mut rx: tokio::sync::broadcast::Receiver<Alert>) {
+    let (mut tx, mut incoming) = socket.split();
+    loop {
+        tokio::select! {
+            msg = incoming.next() => match msg {
+                Some(Ok(m)) if !m.is_close() => continue,
+                _ => break,
             },
-            Err(_) => {
-                // Fallback: generate simple synthetic result
-                RecalibrateResponse {
-                    status: "converged".to_string(),
-                    converged: true,
-                    bias_voltages: corridor_snapshot.lambda_nm.iter().map(|_| 1.2).collect(),
-                    lambda_shifts: corridor_snapshot.lambda_nm.iter().map(|_| 0.0).collect(),
-                    laser_power_adjust: vec![0.0; corridor_snapshot.lambda_nm.len()],
-                    convergence_time_ms: 150,
-                    final_ber: 1.0e-12,
-                    final_eye_margin: 0.8,
-                    power_savings: 10.0,
+            alert = rx.recv() => match alert {
+                Ok(alert) => {
+                    let frame = serde_json::to_string(&alert).unwrap_or_default();
+                    if tx.send(warp::ws::Message::text(frame)).await.is_err() {
+                        break;
+                    }
                 }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                    tracing::warn!("alert subscriber lagged, dropped {} alerts", n);
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            },
+        }
+    }
+    let _ = tx.close().await;
+}
+
+/// Run an allocation in the background and stream it: one event per
+/// `AllocationStage` with `{"stage": ...}`, then `active` carrying the `Corridor`,
+/// or `error` with the usual error body. The allocation completes even if the
+/// client goes away.
+fn allocation_event_stream(
+    service: Arc<CorridorService>,
+    req: CorridorRequest,
+    created_by: String,
+    key: Option<String>,
+) -> impl futures_util::Stream<Item = Result<warp::sse::Event, std::convert::Infallible>> {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<warp::sse::Event>();
+    tokio::spawn(async move {
+        let progress = |stage: AllocationStage| {
+            if let Ok(event) = warp::sse::Event::default().event("stage").json_data(serde_json::json!({"stage": stage})) {
+                let _ = tx.send(event);
+            }
+        };
+        let result = match key {
+            Some(key) => service.allocate_idempotent(req, &created_by, &key, &progress).await,
+            None => service.allocate_with_progress(req, &created_by, &progress).await,
+        };
+        let last = match result {
+            Ok(corridor) => warp::sse::Event::default().event("active").json_data(&corridor),
+            Err(e) => {
+                let e = CorridorError::from(e);
+                warp::sse::Event::default().event("error").json_data(serde_json::json!({"error": e.to_string(), "code": e.code()}))
             }
         };
+        if let Ok(event) = last {
+            let _ = tx.send(event);
+        }
+    });
+    futures_util::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|event| (Ok(event), rx)) })
+}
 
-        // Mark active again
-        {
-            let mut corridors = self.corridors.write().await;
-            if let Some(c) = corridors.get_mut(id) {
-                c.status = CorridorStatus::Active;
+/// SSE events for one corridor's status transitions. The receiver is dropped, and
+/// the subscription with it, when the client disconnects and warp drops the stream.
+fn status_event_stream(
+    id: String,
+    rx: tokio::sync::broadcast::Receiver<StatusEvent>,
+) -> impl futures_util::Stream<Item = Result<warp::sse::Event, std::convert::Infallible>> {
+    futures_util::stream::unfold(rx, move |mut rx| {
+        let id = id.clone();
+        async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) if event.corridor_id == id => {
+                        let sse = warp::sse::Event::default().event("status").json_data(&event).ok()?;
+                        return Some((Ok(sse), rx));
+                    }
+                    Ok(_) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                        tracing::warn!("status event subscriber for {} lagged, dropped {} events", id, n);
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => return None,
+                }
             }
         }
+    })
+}
 
-        Ok(out)
+/// Resolves on Ctrl-C or, on unix, SIGTERM.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sig) => {
+                sig.recv().await;
+            }
+            Err(e) => {
+                tracing::warn!("cannot install SIGTERM handler: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
     }
+    tracing::info!("shutdown signal received, draining connections");
+}
 
-    fn verify_attestation(&self, ticket: &str) -> Result<bool> {
-        let base = self.attestd_url.trim_end_matches('/');
-        let host_port = base.trim_start_matches("http://").trim_start_matches("https://");
-        let host_only = host_port.split('/').next().unwrap_or(host_port);
-        let addr = if host_only.contains(':') { host_only.to_string() } else { format!("{}:{}", host_only, 80) };
-        let path = format!("/v1/attest/{}", ticket);
-        let mut stream = TcpStream::connect(addr.clone())
-            .map_err(|e| anyhow::anyhow!(format!("connect {} failed: {}", addr, e)))?;
-        let req = format!("GET {p} HTTP/1.1\r\nHost: {h}\r\nConnection: close\r\n\r\n", p = path, h = host_only);
-        stream.write_all(req.as_bytes()).ok();
-        let mut buf = Vec::new();
-        stream.read_to_end(&mut buf).ok();
-        let resp = String::from_utf8_lossy(&buf);
-        let parts: Vec<&str> = resp.split("\r\n\r\n").collect();
-        if parts.len() < 2 { return Ok(false); }
-        if let Some(status) = resp.lines().next() { if !status.contains("200") { return Ok(false); } }
-        let body = parts[parts.len()-1];
-        let v: serde_json::Value = serde_json::from_str(body).unwrap_or(serde_json::json!({}));
-        Ok(v.get("valid").and_then(|x| x.as_bool()).unwrap_or(false))
-    }
+/// Listen address from `CORRD_BIND_ADDR` (default `0.0.0.0`) and `CORRD_PORT` (default `8080`).
+#[derive(clap::Parser)]
+#[command(name = "corrd", about = "Photonic Corridor Management Daemon")]
+struct Args {
+    /// TOML or JSON file of tunables (or `CORRD_CONFIG`); env vars override its values.
+    #[arg(long)]
+    config: Option<String>,
+}
 
-    fn update_lane_metrics(&self, corridor: &Corridor, telem: Option<&TelemetryData>) {
-        let ber = telem.map(|t| t.ber).unwrap_or(1.0e-12);
-        let temp = telem.map(|t| t.temp_c).unwrap_or(40.0);
-        let power = telem.map(|t| t.power_pj_per_bit).unwrap_or(1.0);
-        let util = telem.map(|t| t.utilization_percent).unwrap_or(0.0);
-        let errs = telem.map(|t| t.error_count as f64).unwrap_or(0.0);
-        for (i, lambda) in corridor.lambda_nm.iter().enumerate() {
-            let lane = (i + 1).to_string();
-            let lam = lambda.to_string();
-            let jf = (i as f64) * 0.00001;
-            self.m_lane_ber.with_label_values(&[&corridor.id, &lane, &lam]).set(ber * (1.0 + jf));
-            self.m_lane_temp.with_label_values(&[&corridor.id, &lane, &lam]).set(temp + (i as f64) * 0.05);
-            self.m_lane_power.with_label_values(&[&corridor.id, &lane, &lam]).set(power + (i as f64) * 0.005);
-            self.m_lane_util.with_label_values(&[&corridor.id, &lane, &lam]).set(util);
-            self.m_lane_err.with_label_values(&[&corridor.id, &lane, &lam]).set(errs);
-        }
+/// Period of a background task from `key` in ms; zero is refused because
+/// `tokio::time::interval` panics on it.
+fn interval_from_env(key: &str, default_ms: u64) -> Result<std::time::Duration> {
+    let ms: u64 = env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default_ms);
+    if ms == 0 {
+        return Err(anyhow::anyhow!("{} must be greater than 0", key));
     }
+    Ok(std::time::Duration::from_millis(ms))
+}
 
-    pub async fn list_corridors(&self) -> Vec<Corridor> {
-        let corridors = self.corridors.read().await;
-        corridors.values().cloned().collect()
-    }
+fn bind_addr_from_env() -> Result<std::net::SocketAddr> {
+    let host = env::var("CORRD_BIND_ADDR").unwrap_or_else(|_| "0.0.0.0".to_string());
+    let port = env::var("CORRD_PORT").unwrap_or_else(|_| "8080".to_string());
+    let ip: std::net::IpAddr = host
+        .parse()
+        .map_err(|e| anyhow::anyhow!("invalid CORRD_BIND_ADDR '{}': {}", host, e))?;
+    let port: u16 = port
+        .parse()
+        .map_err(|e| anyhow::anyhow!("invalid CORRD_PORT '{}': {}", port, e))?;
+    Ok((ip, port).into())
+}
 
-    pub async fn get_corridor(&self, id: &str) -> Result<Corridor> {
-        let corridors = self.corridors.read().await;
-        corridors.get(id)
-            .cloned()
-            .ok_or_else(|| anyhow::anyhow!("Corridor {} not found", id))
+/// Whether a scraper's `Accept` header ranks the delimited protobuf exposition
+/// format strictly above text; ties and absent headers get text.
+fn wants_protobuf(accept: Option<&str>) -> bool {
+    let (mut protobuf_q, mut text_q) = (0.0_f64, 0.0_f64);
+    for entry in accept.unwrap_or_default().split(',') {
+        let mut parts = entry.split(';').map(str::trim);
+        let media = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let mut q = 1.0;
+        let mut encoding = None;
+        for param in parts {
+            match param.split_once('=') {
+                Some(("q", v)) => q = v.trim().parse().unwrap_or(0.0),
+                Some(("encoding", v)) => encoding = Some(v.trim().to_string()),
+                _ => {}
+            }
+        }
+        match media.as_str() {
+            "application/vnd.google.protobuf" if encoding.as_deref().is_none_or(|e| e == "delimited") => {
+                protobuf_q = protobuf_q.max(q)
+            }
+            "text/plain" | "*/*" => text_q = text_q.max(q),
+            _ => {}
+        }
     }
+    protobuf_q > 0.0 && protobuf_q > text_q
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize tracing
-    tracing_subscriber::fmt::init();
-
-    let service = Arc::new(CorridorService::new());
+/// Span wrapping each request, keyed by the caller's `X-Request-Id` or a fresh
+/// UUID, so everything logged while serving it (downstream calls included) carries the id.
+fn request_span(info: warp::trace::Info) -> tracing::Span {
+    let request_id = info
+        .request_headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    tracing::info_span!("request", request_id = %request_id, method = %info.method(), path = %info.path())
+}
 
+/// Every HTTP route over `service`, with auth, rate limiting, error rendering,
+/// CORS and request tracing applied. Building it only reads the auth and
+/// rate-limit env vars, so tests can drive it with `warp::test::request()`.
+pub fn build_routes(
+    service: Arc<CorridorService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     // CORS filter
     let cors = warp::cors()
         .allow_any_origin()
-        .allow_headers(vec!["content-type"])
+        .allow_headers(vec!["content-type", "authorization", "x-request-id", "idempotency-key"])
         .allow_methods(vec!["GET", "POST", "PATCH", "DELETE"]);
 
     // Health check endpoint
     let health = warp::path("health")
+        .and(warp::path::end())
         .and(warp::get())
         .map(|| warp::reply::json(&serde_json::json!({"status": "ok"})));
 
+    // OpenAPI document
+    let openapi_route = warp::path!("openapi.json")
+        .and(warp::get())
+        .map(|| warp::reply::json(&openapi::document()));
+
+    // Dependency reachability probe
+    let service_hr = service.clone();
+    let health_ready = warp::path!("health" / "ready")
+        .and(warp::get())
+        .and(warp::any().map(move || service_hr.clone()))
+        .then(|service: Arc<CorridorService>| async move {
+            let (healthy, report) = service.dependency_health().await;
+            let code = if healthy { warp::http::StatusCode::OK } else { warp::http::StatusCode::SERVICE_UNAVAILABLE };
+            warp::reply::with_status(warp::reply::json(&report), code)
+        });
+
+    // Self-test of the critical paths
+    let service_st = service.clone();
+    let selftest = warp::path!("v1" / "selftest")
+        .and(warp::get())
+        .and(warp::any().map(move || service_st.clone()))
+        .then(|service: Arc<CorridorService>| async move {
+            let (passed, report) = service.selftest().await;
+            let code = if passed { warp::http::StatusCode::OK } else { warp::http::StatusCode::SERVICE_UNAVAILABLE };
+            warp::reply::with_status(warp::reply::json(&report), code)
+        });
+
+    // Readiness endpoint (dependency latency)
+    let service_ready = service.clone();
+    let ready = warp::path("ready")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::any().map(move || service_ready.clone()))
+        .map(|service: Arc<CorridorService>| warp::reply::json(&service.readiness()));
+
     // Allocate corridor endpoint
     let service1 = service.clone();
     let allocate = warp::path("v1")
         .and(warp::path("corridors"))
+        .and(warp::path::end())
         .and(warp::post())
-        .and(warp::body::json())
+        .and(warp::query::<AllocateQuery>())
+        .and(warp::header::optional::<String>("x-user"))
+        .and(warp::header::optional::<String>("idempotency-key"))
+        .and(rejection::json_body())
         .and(warp::any().map(move || service1.clone()))
-        .and_then(|req: CorridorRequest, service: Arc<CorridorService>| async move {
-            match service.allocate_corridor(req).await {
-                Ok(corridor) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+        .and_then(|query: AllocateQuery, user: Option<String>, key: Option<String>, req: CorridorRequest, service: Arc<CorridorService>| async move {
+            let created_by = user.filter(|u| !u.is_empty()).unwrap_or_else(anonymous_user);
+            let key = key.filter(|k| !k.is_empty());
+            if query.stream {
+                let events = allocation_event_stream(service, req, created_by, key);
+                return Ok::<_, warp::Rejection>(Box::new(warp::sse::reply(events)) as Box<dyn warp::Reply>);
+            }
+            let result = match key {
+                Some(key) => service.allocate_idempotent(req, &created_by, &key, &|_| {}).await,
+                None => service.allocate_corridor(req, &created_by).await,
+            };
+            match result {
+                Ok(corridor) => Ok(Box::new(warp::reply::with_status(
                     warp::reply::json(&corridor),
                     warp::http::StatusCode::CREATED,
+                ))),
+                Err(e) => Err(error::reject(e)),
+            }
+        });
+
+    // Clone endpoint
+    let service_clone = service.clone();
+    let clone = warp::path!("v1" / "corridors" / String / "clone")
+        .and(warp::post())
+        .and(warp::header::optional::<String>("x-user"))
+        .and(rejection::json_body())
+        .and(warp::any().map(move || service_clone.clone()))
+        .and_then(
+            |id: String, user: Option<String>, overrides: serde_json::Map<String, serde_json::Value>, service: Arc<CorridorService>| async move {
+                let created_by = user.filter(|u| !u.is_empty()).unwrap_or_else(anonymous_user);
+                match service.clone_corridor(&id, overrides, &created_by).await {
+                    Ok(corridor) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                        warp::reply::json(&corridor),
+                        warp::http::StatusCode::CREATED,
+                    )),
+                    Err(e) => Err(error::reject(e)),
+                }
+            },
+        );
+
+    // Atomic batch allocation endpoint
+    let service_ba = service.clone();
+    let batch_allocate = warp::path!("v1" / "corridors" / "batch")
+        .and(warp::post())
+        .and(warp::header::optional::<String>("x-user"))
+        .and(rejection::json_body())
+        .and(warp::any().map(move || service_ba.clone()))
+        .and_then(|user: Option<String>, reqs: Vec<CorridorRequest>, service: Arc<CorridorService>| async move {
+            if reqs.is_empty() {
+                return Err(error::reject(anyhow::anyhow!("batch must contain at least one request")));
+            }
+            let created_by = user.filter(|u| !u.is_empty()).unwrap_or_else(anonymous_user);
+            let total = reqs.len();
+            match service.allocate_batch(reqs, &created_by).await {
+                Ok(created) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&created),
+                    warp::http::StatusCode::CREATED,
                 )),
-                Err(e) => Ok(warp::reply::with_status(
-                    warp::reply::json(&serde_json::json!({"error": e.to_string()})),
-                    warp::http::StatusCode::BAD_REQUEST,
-                )),
+                Err(BatchError::Rejected(e)) => Err(error::reject(e)),
+                Err(BatchError::Items(results)) => {
+                    let failed = results.iter().filter(|r| !r.ok).count();
+                    Ok(warp::reply::with_status(
+                        warp::reply::json(&serde_json::json!({
+                            "error": format!("batch rejected: {} of {} requests failed, nothing allocated", failed, total),
+                            "results": results,
+                        })),
+                        warp::http::StatusCode::BAD_REQUEST,
+                    ))
+                }
             }
         });
 
+    // Batch validation (dry run) endpoint
+    let service_bv = service.clone();
+    let batch_validate = warp::path!("v1" / "corridors" / "batch" / "validate")
+        .and(warp::post())
+        .and(warp::query::<BatchValidateQuery>())
+        .and(rejection::json_body())
+        .and(warp::any().map(move || service_bv.clone()))
+        .and_then(|q: BatchValidateQuery, reqs: Vec<CorridorRequest>, service: Arc<CorridorService>| async move {
+            let report = service.validate_batch(&reqs, q.estimate).await;
+            Ok::<_, warp::Rejection>(warp::reply::with_status(
+                warp::reply::json(&report),
+                warp::http::StatusCode::OK,
+            ))
+        });
+
     // Get telemetry endpoint
     let service2 = service.clone();
     let telemetry = warp::path("v1")
         .and(warp::path("corridors"))
         .and(warp::path::param::<String>())
         .and(warp::path("telemetry"))
+        .and(warp::path::end())
         .and(warp::get())
         .and(warp::any().map(move || service2.clone()))
         .and_then(|id: String, service: Arc<CorridorService>| async move {
@@ -489,11 +3388,59 @@ async fn main() -> Result<()> {
                     warp::reply::json(&data),
                     warp::http::StatusCode::OK,
                 )),
-                Err(e) => Ok(warp::reply::with_status(
-                    warp::reply::json(&serde_json::json!({"error": e.to_string()})),
-                    warp::http::StatusCode::NOT_FOUND,
+                Err(e) => Err(error::reject(e)),
+            }
+        });
+
+    let service_lt = service.clone();
+    let lane_telemetry = warp::path!("v1" / "corridors" / String / "telemetry" / "lanes")
+        .and(warp::get())
+        .and(warp::any().map(move || service_lt.clone()))
+        .and_then(|id: String, service: Arc<CorridorService>| async move {
+            match service.get_lane_telemetry(&id).await {
+                Ok(lanes) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&lanes),
+                    warp::http::StatusCode::OK,
+                )),
+                Err(e) => Err(error::reject(e)),
+            }
+        });
+
+    let service_ts = service.clone();
+    let telemetry_summary = warp::path!("v1" / "corridors" / String / "telemetry" / "summary")
+        .and(warp::get())
+        .and(warp::any().map(move || service_ts.clone()))
+        .and_then(|id: String, service: Arc<CorridorService>| async move {
+            match service.get_telemetry_summary(&id).await {
+                Ok(summary) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&summary),
+                    warp::http::StatusCode::OK,
                 )),
+                Err(e) => Err(error::reject(e)),
+            }
+        });
+
+    // Fleet-wide telemetry summary endpoint
+    let service_ft = service.clone();
+    let fleet_telemetry = warp::path!("v1" / "telemetry" / "summary")
+        .and(warp::get())
+        .and(warp::any().map(move || service_ft.clone()))
+        .and_then(|service: Arc<CorridorService>| async move {
+            Ok::<_, warp::Rejection>(warp::reply::json(&service.fleet_telemetry().await))
+        });
+
+    // Live telemetry websocket
+    let service_tws = service.clone();
+    let telemetry_ws = warp::path!("v1" / "corridors" / String / "telemetry" / "ws")
+        .and(warp::ws())
+        .and(warp::query::<TelemetryWsQuery>())
+        .and(warp::any().map(move || service_tws.clone()))
+        .and_then(|id: String, ws: warp::ws::Ws, q: TelemetryWsQuery, service: Arc<CorridorService>| async move {
+            if let Err(e) = service.get_corridor(&id).await {
+                return Err(error::reject(e));
             }
+            let interval = std::time::Duration::from_millis(q.interval_ms.unwrap_or(1000).max(50));
+            Ok(ws.on_upgrade(move |socket| stream_telemetry(socket, service, id, interval)))
         });
 
     // Recalibrate endpoint
@@ -503,7 +3450,7 @@ async fn main() -> Result<()> {
         .and(warp::path::param::<String>())
         .and(warp::path("recalibrate"))
         .and(warp::post())
-        .and(warp::body::json())
+        .and(rejection::json_body())
         .and(warp::any().map(move || service3.clone()))
         .and_then(|id: String, req: RecalibrateRequest, service: Arc<CorridorService>| async move {
             match service.recalibrate(&id, req).await {
@@ -511,10 +3458,21 @@ async fn main() -> Result<()> {
                     warp::reply::json(&response),
                     warp::http::StatusCode::OK,
                 )),
-                Err(e) => Ok(warp::reply::with_status(
-                    warp::reply::json(&serde_json::json!({"error": e.to_string()})),
-                    warp::http::StatusCode::NOT_FOUND,
+                Err(e) => Err(error::reject(e)),
+            }
+        });
+
+    let service_rh = service.clone();
+    let recalibrations = warp::path!("v1" / "corridors" / String / "recalibrations")
+        .and(warp::get())
+        .and(warp::any().map(move || service_rh.clone()))
+        .and_then(|id: String, service: Arc<CorridorService>| async move {
+            match service.recalibration_history(&id).await {
+                Ok(history) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&history),
+                    warp::http::StatusCode::OK,
                 )),
+                Err(e) => Err(error::reject(e)),
             }
         });
 
@@ -522,14 +3480,36 @@ async fn main() -> Result<()> {
     let service4 = service.clone();
     let list_corridors = warp::path("v1")
         .and(warp::path("corridors"))
+        .and(warp::path::end())
         .and(warp::get())
+        .and(warp::query::<ListQuery>())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(warp::header::optional::<String>("accept"))
+        .and(warp::header::optional::<String>("accept-encoding"))
         .and(warp::any().map(move || service4.clone()))
-        .and_then(|service: Arc<CorridorService>| async move {
-            let corridors = service.list_corridors().await;
-            Ok::<_, warp::Rejection>(warp::reply::with_status(
-                warp::reply::json(&corridors),
-                warp::http::StatusCode::OK,
-            ))
+        .and_then(|query: ListQuery, raw: HashMap<String, String>, accept: Option<String>, encoding: Option<String>, service: Arc<CorridorService>| async move {
+            let query = query.with_labels(raw);
+            let fields = match query.validate().and_then(|_| parse_fields(query.fields.as_deref())) {
+                Ok(fields) => fields,
+                Err(e) => return Err(error::reject(e)),
+            };
+            if accept.as_deref().map(|a| a.contains(NDJSON_CONTENT_TYPE)).unwrap_or(false) {
+                let body = hyper::Body::wrap_stream(ndjson_corridor_stream(service, query, fields));
+                let reply = warp::reply::with_header(
+                    warp::reply::Response::new(body),
+                    "Content-Type",
+                    NDJSON_CONTENT_TYPE,
+                );
+                return Ok::<_, warp::Rejection>(Box::new(reply) as Box<dyn warp::Reply>);
+            }
+            let page = service.list_corridors(&query).await;
+            let page = CorridorPage {
+                items: page.items.iter().map(|c| project_corridor(c, fields.as_deref(), service.now())).collect::<Vec<_>>(),
+                total: page.total,
+                offset: page.offset,
+            };
+            let body = serde_json::to_vec(&page).map_err(|e| error::reject(e.into()))?;
+            Ok(Box::new(compress::reply(body, "application/json", encoding.as_deref())))
         });
 
     // Get corridor endpoint
@@ -537,47 +3517,431 @@ async fn main() -> Result<()> {
     let get_corridor = warp::path("v1")
         .and(warp::path("corridors"))
         .and(warp::path::param::<String>())
+        .and(warp::path::end())
         .and(warp::get())
+        .and(warp::query::<FieldsQuery>())
         .and(warp::any().map(move || service5.clone()))
+        .and_then(|id: String, query: FieldsQuery, service: Arc<CorridorService>| async move {
+            let fields = match parse_fields(query.fields.as_deref()) {
+                Ok(fields) => fields,
+                Err(e) => return Err(error::reject(e)),
+            };
+            let found = if query.include_archived {
+                service.get_corridor_or_archived(&id).await
+            } else {
+                service.get_corridor(&id).await
+            };
+            match found {
+                Ok(corridor) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&project_corridor(&corridor, fields.as_deref(), service.now())),
+                    warp::http::StatusCode::OK,
+                )),
+                Err(e) => Err(error::reject(e)),
+            }
+        });
+
+    let service_archived = service.clone();
+    let archived = warp::path!("v1" / "corridors" / "archived")
+        .and(warp::get())
+        .and(warp::any().map(move || service_archived.clone()))
+        .and_then(|service: Arc<CorridorService>| async move {
+            let list = service.archived_corridors().await;
+            Ok::<_, warp::Rejection>(warp::reply::with_status(warp::reply::json(&list), warp::http::StatusCode::OK))
+        });
+
+    let service_purge = service.clone();
+    let purge = warp::path!("v1" / "corridors" / "archived" / String)
+        .and(warp::delete())
+        .and(warp::any().map(move || service_purge.clone()))
+        .and_then(|id: String, service: Arc<CorridorService>| async move {
+            match service.purge_archived(&id).await {
+                Ok(corridor) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&corridor),
+                    warp::http::StatusCode::OK,
+                )),
+                Err(e) => Err(error::reject(e)),
+            }
+        });
+
+    let service_del = service.clone();
+    let deallocate = warp::path!("v1" / "corridors" / String)
+        .and(warp::delete())
+        .and(warp::any().map(move || service_del.clone()))
+        .and_then(|id: String, service: Arc<CorridorService>| async move {
+            match service.deallocate_corridor(&id).await {
+                Ok(corridor) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&corridor),
+                    warp::http::StatusCode::OK,
+                )),
+                Err(e) => Err(error::reject(e)),
+            }
+        });
+
+    let service_patch = service.clone();
+    let update = warp::path!("v1" / "corridors" / String)
+        .and(warp::patch())
+        .and(rejection::json_body())
+        .and(warp::any().map(move || service_patch.clone()))
+        .and_then(|id: String, patch: CorridorPatch, service: Arc<CorridorService>| async move {
+            match service.update_corridor(&id, patch).await {
+                Ok(corridor) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&corridor),
+                    warp::http::StatusCode::OK,
+                )),
+                Err(e) => Err(error::reject(e)),
+            }
+        });
+
+    // Acknowledge error endpoint
+    let service_ack = service.clone();
+    let ack = warp::path!("v1" / "corridors" / String / "ack")
+        .and(warp::post())
+        .and(warp::header::optional::<String>("x-user"))
+        .and(rejection::json_body())
+        .and(warp::any().map(move || service_ack.clone()))
+        .and_then(|id: String, user: Option<String>, req: AckRequest, service: Arc<CorridorService>| async move {
+            let by = user.filter(|u| !u.is_empty()).unwrap_or_else(anonymous_user);
+            match service.acknowledge(&id, &by, req).await {
+                Ok(corridor) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&corridor),
+                    warp::http::StatusCode::OK,
+                )),
+                Err(e) => Err(error::reject(e)),
+            }
+        });
+
+    // Maintenance drain/resume endpoints
+    let service_mnt = service.clone();
+    let maintenance = warp::path!("v1" / "corridors" / String / "maintenance")
+        .and(warp::post())
+        .and(warp::any().map(move || service_mnt.clone()))
+        .and_then(|id: String, service: Arc<CorridorService>| async move {
+            match service.enter_maintenance(&id).await {
+                Ok(corridor) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&corridor),
+                    warp::http::StatusCode::OK,
+                )),
+                Err(e) => Err(error::reject(e)),
+            }
+        });
+
+    let service_resume = service.clone();
+    let resume = warp::path!("v1" / "corridors" / String / "resume")
+        .and(warp::post())
+        .and(warp::any().map(move || service_resume.clone()))
         .and_then(|id: String, service: Arc<CorridorService>| async move {
-            match service.get_corridor(&id).await {
+            match service.resume(&id).await {
+                Ok(corridor) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&corridor),
+                    warp::http::StatusCode::OK,
+                )),
+                Err(e) => Err(error::reject(e)),
+            }
+        });
+
+    // Fault injection endpoint
+    let service_fault = service.clone();
+    let fault = warp::path!("v1" / "corridors" / String / "fault")
+        .and(warp::post())
+        .and(rejection::json_body())
+        .and(warp::any().map(move || service_fault.clone()))
+        .and_then(|id: String, req: FaultRequest, service: Arc<CorridorService>| async move {
+            match service.inject_fault(&id, req).await {
                 Ok(corridor) => Ok::<_, warp::Rejection>(warp::reply::with_status(
                     warp::reply::json(&corridor),
                     warp::http::StatusCode::OK,
                 )),
-                Err(e) => Ok(warp::reply::with_status(
-                    warp::reply::json(&serde_json::json!({"error": e.to_string()})),
-                    warp::http::StatusCode::NOT_FOUND,
+                Err(e) => Err(error::reject(e)),
+            }
+        });
+
+    // Synthetic load benchmark endpoint
+    let service_bench = service.clone();
+    let benchmark = warp::path!("v1" / "corridors" / String / "benchmark")
+        .and(warp::post())
+        .and(rejection::json_body())
+        .and(warp::any().map(move || service_bench.clone()))
+        .and_then(|id: String, req: BenchmarkRequest, service: Arc<CorridorService>| async move {
+            match service.benchmark(&id, req).await {
+                Ok(report) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&report),
+                    warp::http::StatusCode::OK,
+                )),
+                Err(e) => Err(error::reject(e)),
+            }
+        });
+
+    // Wavelength grid capacity endpoint
+    let service_cap = service.clone();
+    let capacity = warp::path!("v1" / "topology" / "capacity")
+        .and(warp::get())
+        .and(warp::query::<CapacityQuery>())
+        .and(warp::any().map(move || service_cap.clone()))
+        .and_then(|q: CapacityQuery, service: Arc<CorridorService>| async move {
+            match service.topology_capacity(&q).await {
+                Ok(report) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&report),
+                    warp::http::StatusCode::OK,
+                )),
+                Err(e) => Err(error::reject(e)),
+            }
+        });
+
+    // Capabilities endpoint
+    let service_caps = service.clone();
+    let capabilities = warp::path!("v1" / "capabilities")
+        .and(warp::get())
+        .and(warp::any().map(move || service_caps.clone()))
+        .map(|service: Arc<CorridorService>| warp::reply::json(&service.capabilities()));
+
+    // Receipt verification key
+    let service_pk = service.clone();
+    let pubkey = warp::path!("v1" / "pubkey")
+        .and(warp::get())
+        .and(warp::any().map(move || service_pk.clone()))
+        .map(|service: Arc<CorridorService>| {
+            warp::reply::json(&serde_json::json!({
+                "alg": "ed25519",
+                "public_key": service.receipt_public_key(),
+                "receipt_version": receipt::RECEIPT_VERSION,
+            }))
+        });
+
+    // Build metadata
+    let version_route = warp::path!("v1" / "version")
+        .and(warp::get())
+        .map(|| warp::reply::json(&version::build_info()));
+
+    // Fleet alert stream
+    let service_alerts = service.clone();
+    let alerts_ws = warp::path!("v1" / "alerts" / "ws")
+        .and(warp::ws())
+        .and(warp::any().map(move || service_alerts.clone()))
+        .map(|ws: warp::ws::Ws, service: Arc<CorridorService>| {
+            let rx = service.subscribe_alerts();
+            ws.on_upgrade(move |socket| stream_alerts(socket, rx))
+        });
+
+    // Status transition stream
+    let service_events = service.clone();
+    let status_events = warp::path!("v1" / "corridors" / String / "events")
+        .and(warp::get())
+        .and(warp::any().map(move || service_events.clone()))
+        .and_then(|id: String, service: Arc<CorridorService>| async move {
+            match service.subscribe_status(&id).await {
+                Ok(rx) => Ok::<_, warp::Rejection>(warp::sse::reply(
+                    warp::sse::keep_alive().stream(status_event_stream(id, rx)),
                 )),
+                Err(e) => Err(error::reject(e)),
             }
         });
 
     // Expose Prometheus metrics
+    let service_metrics = service.clone();
     let metrics_route = warp::path("metrics")
         .and(warp::get())
-        .map(|| {
-            let encoder = TextEncoder::new();
-            let metric_families = prometheus::gather();
+        .and(warp::header::optional::<String>("accept"))
+        .and(warp::header::optional::<String>("accept-encoding"))
+        .and(warp::any().map(move || service_metrics.clone()))
+        .then(|accept: Option<String>, encoding: Option<String>, service: Arc<CorridorService>| async move {
+            service.update_uptime_metrics().await;
+            let metric_families = service.gather_metrics();
             let mut buffer = Vec::new();
-            let _ = encoder.encode(&metric_families, &mut buffer);
-            let body = String::from_utf8_lossy(&buffer).to_string();
-            warp::reply::with_header(body, "Content-Type", encoder.format_type())
+            let format_type = if wants_protobuf(accept.as_deref()) {
+                let encoder = prometheus::ProtobufEncoder::new();
+                let _ = encoder.encode(&metric_families, &mut buffer);
+                encoder.format_type().to_string()
+            } else {
+                let encoder = TextEncoder::new();
+                let _ = encoder.encode(&metric_families, &mut buffer);
+                encoder.format_type().to_string()
+            };
+            compress::reply(buffer, &format_type, encoding.as_deref())
+        });
+
+    let service_cm = service.clone();
+    let corridor_metrics = warp::path!("v1" / "corridors" / String / "metrics")
+        .and(warp::get())
+        .and(warp::any().map(move || service_cm.clone()))
+        .and_then(|id: String, service: Arc<CorridorService>| async move {
+            match service.corridor_metrics(&id).await {
+                Ok(body) => Ok::<_, warp::Rejection>(Box::new(warp::reply::with_header(
+                    body,
+                    "Content-Type",
+                    TextEncoder::new().format_type(),
+                )) as Box<dyn warp::Reply>),
+                Err(e) => Err(error::reject(e)),
+            }
         });
 
     // Combine all routes
-    let routes = health
-        .or(allocate)
-        .or(telemetry)
-        .or(recalibrate)
-        .or(list_corridors)
-        .or(get_corridor)
-        .or(metrics_route)
-        .with(cors);
-
-    println!("Starting CorridorOS corrd daemon on :8080");
-    warp::serve(routes)
-        .run(([0, 0, 0, 0], 8080))
-        .await;
+    let auth_config = Arc::new(auth::AuthConfig::from_env());
+    auth::authorize(auth_config.clone())
+        .and(ratelimit::limit(Arc::new(ratelimit::RateLimiter::from_env()), auth_config))
+        .and(
+            health
+                .or(health_ready)
+                .or(ready)
+                .or(batch_validate)
+                .or(batch_allocate)
+                .or(ack)
+                .or(fault)
+                .or(benchmark)
+                .or(clone)
+                .or(maintenance)
+                .or(resume)
+                .or(allocate)
+                .or(telemetry_ws)
+                .or(lane_telemetry)
+                .or(telemetry_summary)
+                .or(fleet_telemetry)
+                .or(telemetry)
+                .or(recalibrate)
+                .or(recalibrations)
+                .or(list_corridors)
+                .or(archived)
+                .or(purge)
+                .or(get_corridor)
+                .or(deallocate)
+                .or(update)
+                .or(alerts_ws)
+                .or(status_events)
+                .or(pubkey)
+                .or(version_route)
+                .or(selftest)
+                .or(capacity)
+                .or(capabilities)
+                .or(corridor_metrics)
+                .or(metrics_route)
+                .or(openapi_route)
+        )
+        .recover(rejection::handle_rejection)
+        .with(cors)
+        .with(warp::trace(request_span))
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    if let Some(path) = <Args as clap::Parser>::parse().config.or_else(|| env::var("CORRD_CONFIG").ok()) {
+        config::Config::load(&path)?.apply_to_env();
+    }
+
+    // Initialize tracing; CORRD_LOG_FORMAT=json emits one JSON object per event
+    // with the enclosing request span (and its request_id) attached.
+    if env::var("CORRD_LOG_FORMAT").is_ok_and(|f| f == "json") {
+        tracing_subscriber::fmt().json().init();
+    } else {
+        tracing_subscriber::fmt::init();
+    }
+
+    let addr = bind_addr_from_env()?;
+    let service = Arc::new(CorridorService::with_verifier(attestation::verifier_from_env()?)?);
+
+    let bootstrapped = service.bootstrap_from_env().await?;
+    if bootstrapped > 0 {
+        tracing::info!("bootstrapped {} corridors", bootstrapped);
+    }
+
+    // Periodically reconcile lane metrics with the corridor map
+    let reconcile_interval = interval_from_env("CORRD_RECONCILE_INTERVAL_MS", 60000)?;
+    let service_rec = service.clone();
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(reconcile_interval);
+        loop {
+            tick.tick().await;
+            service_rec.reconcile_metrics().await;
+        }
+    });
+
+    // Periodically expire corridors whose attestation ticket has lapsed
+    let attestation_check = interval_from_env("CORRD_ATTESTATION_CHECK_INTERVAL_MS", 30000)?;
+    let service_att = service.clone();
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(attestation_check);
+        loop {
+            tick.tick().await;
+            service_att.check_attestation_expiry().await;
+        }
+    });
+
+    #[cfg(feature = "grpc")]
+    grpc::spawn(service.clone(), addr)?;
+
+    let routes = build_routes(service.clone());
+
+    // HTTP/1.1 and h2c (prior knowledge) are both accepted on the same port;
+    // HTTP/2 lets dashboards multiplex many requests over one connection.
+    let h2_max_streams: u32 = env::var("CORRD_HTTP2_MAX_STREAMS").ok().and_then(|v| v.parse().ok()).unwrap_or(256);
+    let h2_keepalive_ms: u64 = env::var("CORRD_HTTP2_KEEPALIVE_MS").ok().and_then(|v| v.parse().ok()).unwrap_or(20000);
+    let h2_keepalive = std::time::Duration::from_millis(h2_keepalive_ms);
+
+    let tls_policy = tls::TlsPolicy::from_env()?;
+    if let Some(tls_config) = tls::server_config_from_env(&tls_policy)? {
+        println!("Starting CorridorOS corrd daemon on {} (TLS, HTTP/1.1 + h2, min {:?})", addr, tls_policy.min_version);
+        let acceptor = tokio_rustls::TlsAcceptor::from(tls_config);
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        let shutdown = shutdown_signal();
+        tokio::pin!(shutdown);
+        loop {
+            let accepted = tokio::select! {
+                accepted = listener.accept() => accepted,
+                _ = &mut shutdown => break,
+            };
+            // Accept errors (EMFILE, ECONNABORTED, ...) are transient; back off
+            // briefly rather than taking the whole daemon down.
+            let (stream, peer) = match accepted {
+                Ok(conn) => conn,
+                Err(e) => {
+                    tracing::warn!("accept failed: {}", e);
+                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                    continue;
+                }
+            };
+            let acceptor = acceptor.clone();
+            let svc = ratelimit::with_peer(warp::service(routes.clone()), peer);
+            tokio::spawn(async move {
+                // Handshakes below the configured version or cipher policy fail here.
+                let tls_stream = match acceptor.accept(stream).await {
+                    Ok(s) => s,
+                    Err(e) => {
+                        tracing::warn!("TLS handshake with {} refused: {}", peer, e);
+                        return;
+                    }
+                };
+                let (_, conn) = tls_stream.get_ref();
+                tracing::info!(
+                    "TLS connection from {} negotiated {:?} {:?}",
+                    peer,
+                    conn.protocol_version(),
+                    conn.negotiated_cipher_suite().map(|s| s.suite())
+                );
+                let mut http = hyper::server::conn::Http::new();
+                http.http2_max_concurrent_streams(h2_max_streams)
+                    .http2_keep_alive_interval(Some(h2_keepalive))
+                    .http2_adaptive_window(true);
+                if let Err(e) = http.serve_connection(tls_stream, svc).with_upgrades().await {
+                    tracing::debug!("connection from {} closed: {}", peer, e);
+                }
+            });
+        }
+        service.shutdown().await;
+        return Ok(());
+    }
+
+    let make_svc = hyper::service::make_service_fn(move |conn: &hyper::server::conn::AddrStream| {
+        let svc = ratelimit::with_peer(warp::service(routes.clone()), conn.remote_addr());
+        async move { Ok::<_, std::convert::Infallible>(svc) }
+    });
+
+    println!("Starting CorridorOS corrd daemon on {} (HTTP/1.1 + h2c)", addr);
+    hyper::Server::bind(&addr)
+        .http2_max_concurrent_streams(h2_max_streams)
+        .http2_keep_alive_interval(Some(h2_keepalive))
+        .http2_adaptive_window(true)
+        .serve(make_svc)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+    service.shutdown().await;
 
     Ok(())
 }
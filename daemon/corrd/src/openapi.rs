@@ -0,0 +1,521 @@
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::JsonSchema;
+use serde_json::{json, Value};
+
+use crate::latency::LatencySummary;
+use crate::wavelength::CapacityReport;
+use crate::{
+    AckRequest, BatchValidation, BenchmarkReport, BenchmarkRequest, Capabilities, Corridor, CorridorRequest, FaultRequest, FleetTelemetry, LaneTelemetry, RecalibrateRequest,
+    RecalibrateResponse, RecalibrationRecord, StatusEvent, TelemetryData, TelemetrySummary,
+};
+
+fn schema_ref<T: JsonSchema>(gen: &mut SchemaGenerator) -> Value {
+    serde_json::to_value(gen.subschema_for::<T>()).unwrap_or_default()
+}
+
+fn json_content(schema: Value) -> Value {
+    json!({"content": {"application/json": {"schema": schema}}})
+}
+
+fn id_param() -> Value {
+    json!({"name": "id", "in": "path", "required": true, "schema": {"type": "string"}})
+}
+
+/// OpenAPI 3.0 document for the corridor API. Component schemas are derived from
+/// the same structs the handlers (de)serialize, so they can't drift from the wire format.
+/// Paths are listed by hand: every route in `build_routes` needs an entry here.
+pub fn document() -> Value {
+    let mut gen = SchemaSettings::openapi3().into_generator();
+    let request = schema_ref::<CorridorRequest>(&mut gen);
+    let corridor = schema_ref::<Corridor>(&mut gen);
+    let telemetry = schema_ref::<TelemetryData>(&mut gen);
+    let lane_telemetry = schema_ref::<LaneTelemetry>(&mut gen);
+    let telemetry_summary = schema_ref::<TelemetrySummary>(&mut gen);
+    let fleet_telemetry = schema_ref::<FleetTelemetry>(&mut gen);
+    let recal_request = schema_ref::<RecalibrateRequest>(&mut gen);
+    let recal_response = schema_ref::<RecalibrateResponse>(&mut gen);
+    let recal_record = schema_ref::<RecalibrationRecord>(&mut gen);
+    let fault = schema_ref::<FaultRequest>(&mut gen);
+    let benchmark_request = schema_ref::<BenchmarkRequest>(&mut gen);
+    let benchmark_report = schema_ref::<BenchmarkReport>(&mut gen);
+    let batch_validation = schema_ref::<BatchValidation>(&mut gen);
+    let ack = schema_ref::<AckRequest>(&mut gen);
+    let capabilities = schema_ref::<Capabilities>(&mut gen);
+    let capacity = schema_ref::<CapacityReport>(&mut gen);
+    let latency_summary = schema_ref::<LatencySummary>(&mut gen);
+    let status_event = schema_ref::<StatusEvent>(&mut gen);
+    let selftest = json!({
+        "type": "object",
+        "properties": {
+            "status": {"type": "string", "enum": ["pass", "fail"]},
+            "checks": {"type": "array", "items": {
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string"},
+                    "required": {"type": "boolean"},
+                    "passed": {"type": "boolean"},
+                    "duration_ms": {"type": "number"},
+                    "error": {"type": "string", "nullable": true}
+                }
+            }}
+        }
+    });
+    let dependency_health = json!({
+        "type": "object",
+        "properties": {
+            "status": {"type": "string", "enum": ["ready", "unavailable"]},
+            "services": {"type": "object", "additionalProperties": {
+                "type": "object",
+                "properties": {
+                    "url": {"type": "string"},
+                    "required": {"type": "boolean"},
+                    "reachable": {"type": "boolean"},
+                    "error": {"type": "string", "nullable": true}
+                }
+            }}
+        }
+    });
+    let error = json!({
+        "type": "object",
+        "properties": {"error": {"type": "string"}, "code": {"type": "string"}},
+        "required": ["error"]
+    });
+    let error_response = |description: &str| {
+        let mut r = json_content(json!({"$ref": "#/components/schemas/Error"}));
+        r["description"] = json!(description);
+        r
+    };
+    let ok = |description: &str, schema: Value| {
+        let mut r = json_content(schema);
+        r["description"] = json!(description);
+        r
+    };
+
+    let mut schemas = serde_json::Map::new();
+    for (name, schema) in gen.take_definitions() {
+        schemas.insert(name, serde_json::to_value(schema).unwrap_or_default());
+    }
+    schemas.insert("Error".to_string(), error);
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {"title": "CorridorOS corrd", "version": env!("CARGO_PKG_VERSION")},
+        "paths": {
+            "/v1/corridors": {
+                "get": {
+                    "summary": "List corridors",
+                    "parameters": [
+                        {"name": "status", "in": "query", "schema": {"type": "string"}},
+                        {"name": "type", "in": "query", "schema": {"type": "string"}},
+                        {"name": "created_by", "in": "query", "schema": {"type": "string"}},
+                        {"name": "fields", "in": "query", "schema": {"type": "string"}},
+                        {"name": "limit", "in": "query", "schema": {"type": "integer"}},
+                        {"name": "offset", "in": "query", "schema": {"type": "integer"}},
+                        {
+                            "name": "label.{key}",
+                            "in": "query",
+                            "description": "Only corridors whose label {key} has this value; repeat for more keys",
+                            "schema": {"type": "string"}
+                        }
+                    ],
+                    "responses": {
+                        "200": ok("One page of corridors", json!({
+                            "type": "object",
+                            "properties": {
+                                "items": {"type": "array", "items": corridor},
+                                "total": {"type": "integer"},
+                                "offset": {"type": "integer"}
+                            }
+                        })),
+                        "400": error_response("Invalid filter")
+                    }
+                },
+                "post": {
+                    "summary": "Allocate a corridor",
+                    "parameters": [
+                        {"name": "Idempotency-Key", "in": "header", "schema": {"type": "string"}},
+                        {
+                            "name": "stream",
+                            "in": "query",
+                            "description": "Reply 200 with text/event-stream: `stage` events ({\"stage\": validating|attesting|estimating}), then `active` with the Corridor or `error` with an Error",
+                            "schema": {"type": "boolean"}
+                        }
+                    ],
+                    "requestBody": json_content(request.clone()),
+                    "responses": {
+                        "200": {"description": "Progress stream, with stream=true", "content": {"text/event-stream": {"schema": {"type": "string"}}}},
+                        "201": ok("Allocated corridor, or the one an earlier request with the same Idempotency-Key created", corridor.clone()),
+                        "400": error_response("Invalid request"),
+                        "401": error_response("Attestation ticket or bearer token missing"),
+                        "403": error_response("Attestation ticket rejected"),
+                        "409": error_response("Idempotency-Key in use by a running request, or its corridor is gone; or a wavelength is already held on medium_id"),
+                        "429": error_response("Caller's rate limit exceeded; see Retry-After"),
+                        "502": error_response("Downstream service failed")
+                    }
+                }
+            },
+            "/v1/corridors/batch": {
+                "post": {
+                    "summary": "Allocate several corridors atomically",
+                    "requestBody": json_content(json!({"type": "array", "items": request.clone()})),
+                    "responses": {
+                        "201": ok("Allocated corridors", json!({"type": "array", "items": corridor.clone()})),
+                        "400": ok("Per-index failures; nothing was allocated", json!({
+                            "type": "object",
+                            "properties": {"error": {"type": "string"}, "results": {"type": "array", "items": {"type": "object"}}}
+                        })),
+                        "409": error_response("A wavelength is already held on its medium_id")
+                    }
+                }
+            },
+            "/v1/corridors/batch/validate": {
+                "post": {
+                    "summary": "Dry-run a batch through validation and attestation without allocating",
+                    "parameters": [
+                        {
+                            "name": "estimate",
+                            "in": "query",
+                            "description": "Also report per-request and total power, cost and wavelength conflicts",
+                            "schema": {"type": "boolean"}
+                        }
+                    ],
+                    "requestBody": json_content(json!({"type": "array", "items": request.clone()})),
+                    "responses": {
+                        "200": ok("Per-index results; valid is true only when every request passed", batch_validation),
+                        "400": error_response("Body is not an array of corridor requests")
+                    }
+                }
+            },
+            "/v1/corridors/{id}": {
+                "parameters": [id_param()],
+                "get": {
+                    "summary": "Get a corridor",
+                    "parameters": [
+                        {"name": "fields", "in": "query", "schema": {"type": "string"}},
+                        {
+                            "name": "include_archived",
+                            "in": "query",
+                            "description": "Also look in the archive of decommissioned corridors",
+                            "schema": {"type": "boolean"}
+                        }
+                    ],
+                    "responses": {"200": ok("Corridor", corridor.clone()), "404": error_response("Unknown corridor")}
+                },
+                "patch": {
+                    "summary": "Update mutable corridor settings",
+                    "requestBody": json_content(json!({
+                        "type": "object",
+                        "properties": {
+                            "priority": {"type": "string", "enum": ["low", "normal", "high", "critical"]},
+                            "pfc": {"type": "boolean"},
+                            "min_gbps": {"type": "integer", "minimum": 0}
+                        }
+                    })),
+                    "responses": {
+                        "200": ok("Updated corridor", corridor.clone()),
+                        "400": error_response("Immutable field or invalid value"),
+                        "404": error_response("Unknown corridor")
+                    }
+                },
+                "delete": {
+                    "summary": "Deallocate a corridor and move it to the archive",
+                    "responses": {
+                        "200": ok("Decommissioned corridor", corridor.clone()),
+                        "404": error_response("Unknown corridor"),
+                        "409": error_response("Bootstrap corridor")
+                    }
+                }
+            },
+            "/v1/corridors/archived": {
+                "get": {
+                    "summary": "List decommissioned corridors, most recent first",
+                    "responses": {"200": ok("Archived corridors", json!({"type": "array", "items": corridor.clone()}))}
+                }
+            },
+            "/v1/corridors/archived/{id}": {
+                "parameters": [id_param()],
+                "delete": {
+                    "summary": "Purge an archived corridor permanently",
+                    "responses": {"200": ok("Purged corridor", corridor.clone()), "404": error_response("Not in the archive")}
+                }
+            },
+            "/v1/corridors/{id}/telemetry": {
+                "parameters": [id_param()],
+                "get": {
+                    "summary": "Current telemetry",
+                    "responses": {"200": ok("Telemetry sample", telemetry), "404": error_response("Unknown corridor")}
+                }
+            },
+            "/v1/corridors/{id}/telemetry/lanes": {
+                "parameters": [id_param()],
+                "get": {
+                    "summary": "Current telemetry per wavelength",
+                    "responses": {
+                        "200": ok("One sample per entry of lambda_nm", json!({"type": "array", "items": lane_telemetry})),
+                        "404": error_response("Unknown corridor")
+                    }
+                }
+            },
+            "/v1/corridors/{id}/telemetry/summary": {
+                "parameters": [id_param()],
+                "get": {
+                    "summary": "Min/max/avg/p95 over the recent telemetry samples",
+                    "responses": {
+                        "200": ok("Rolling window of CORRD_TELEMETRY_WINDOW samples", telemetry_summary),
+                        "404": error_response("Unknown corridor")
+                    }
+                }
+            },
+            "/v1/telemetry/summary": {
+                "get": {
+                    "summary": "Fleet utilization, worst BER and temperature, and total errors from each Active corridor's latest sample",
+                    "responses": {"200": ok("Fleet telemetry totals", fleet_telemetry)}
+                }
+            },
+            "/v1/corridors/{id}/recalibrate": {
+                "parameters": [id_param()],
+                "post": {
+                    "summary": "Recalibrate through HELIOPASS",
+                    "requestBody": json_content(recal_request),
+                    "responses": {
+                        "200": ok("Calibration result", recal_response),
+                        "404": error_response("Unknown corridor"),
+                        "409": error_response("In Maintenance, or a calibration is already in progress")
+                    }
+                }
+            },
+            "/v1/corridors/{id}/recalibrations": {
+                "parameters": [id_param()],
+                "get": {
+                    "summary": "Recent recalibration results, oldest first",
+                    "responses": {
+                        "200": ok("Recalibration history", json!({"type": "array", "items": recal_record})),
+                        "404": error_response("Unknown corridor")
+                    }
+                }
+            },
+            "/v1/corridors/{id}/telemetry/ws": {
+                "parameters": [
+                    id_param(),
+                    {"name": "interval_ms", "in": "query", "description": "Frame interval, at least 50 (default 1000)", "schema": {"type": "integer"}}
+                ],
+                "get": {
+                    "summary": "WebSocket of telemetry samples, one JSON TelemetryData frame per interval",
+                    "responses": {
+                        "101": {"description": "Switching to the WebSocket protocol"},
+                        "404": error_response("Unknown corridor")
+                    }
+                }
+            },
+            "/v1/corridors/{id}/events": {
+                "parameters": [id_param()],
+                "get": {
+                    "summary": "Server-sent events of the corridor's status transitions, one `status` event per change",
+                    "responses": {
+                        "200": {
+                            "description": "Event stream; each event's data is a StatusEvent",
+                            "content": {"text/event-stream": {"schema": status_event}}
+                        },
+                        "404": error_response("Unknown corridor")
+                    }
+                }
+            },
+            "/v1/corridors/{id}/ack": {
+                "parameters": [id_param(), {"name": "x-user", "in": "header", "schema": {"type": "string"}}],
+                "post": {
+                    "summary": "Acknowledge a corridor in error so repeat alerts stay quiet until it recovers",
+                    "requestBody": json_content(ack),
+                    "responses": {
+                        "200": ok("Acknowledged corridor", corridor.clone()),
+                        "404": error_response("Unknown corridor"),
+                        "409": error_response("Corridor is not in error")
+                    }
+                }
+            },
+            "/v1/corridors/{id}/metrics": {
+                "parameters": [id_param()],
+                "get": {
+                    "summary": "Prometheus text for only this corridor's series",
+                    "responses": {
+                        "200": {"description": "Prometheus text exposition", "content": {"text/plain": {"schema": {"type": "string"}}}},
+                        "404": error_response("Unknown corridor")
+                    }
+                }
+            },
+            "/v1/corridors/{id}/maintenance": {
+                "parameters": [id_param()],
+                "post": {
+                    "summary": "Drain a corridor into Maintenance",
+                    "responses": {
+                        "200": ok("Corridor in Maintenance", corridor.clone()),
+                        "404": error_response("Unknown corridor"),
+                        "409": error_response("Already in Maintenance or Calibrating")
+                    }
+                }
+            },
+            "/v1/corridors/{id}/resume": {
+                "parameters": [id_param()],
+                "post": {
+                    "summary": "Return a corridor from Maintenance to Active",
+                    "responses": {
+                        "200": ok("Active corridor", corridor.clone()),
+                        "404": error_response("Unknown corridor"),
+                        "409": error_response("Not in Maintenance")
+                    }
+                }
+            },
+            "/v1/corridors/{id}/fault": {
+                "parameters": [id_param()],
+                "post": {
+                    "summary": "Inject a telemetry fault for testing alerts",
+                    "requestBody": json_content(fault),
+                    "responses": {
+                        "200": ok("Corridor with the fault set", corridor.clone()),
+                        "400": error_response("Invalid fault profile"),
+                        "404": error_response("Unknown corridor")
+                    }
+                }
+            },
+            "/v1/corridors/{id}/clone": {
+                "parameters": [id_param()],
+                "post": {
+                    "summary": "Allocate a new corridor from this one's parameters, with optional field overrides",
+                    "requestBody": json_content(json!({
+                        "type": "object",
+                        "description": "Any CorridorRequest fields; each replaces the source's value. Send {} for an exact copy."
+                    })),
+                    "responses": {
+                        "201": ok("Corridor allocated", corridor.clone()),
+                        "400": error_response("Invalid override or request"),
+                        "403": error_response("Attestation ticket invalid"),
+                        "404": error_response("Unknown source corridor"),
+                        "409": error_response("Wavelengths already held on the medium")
+                    }
+                }
+            },
+            "/v1/corridors/{id}/benchmark": {
+                "parameters": [id_param()],
+                "post": {
+                    "summary": "Ramp simulated load up to min_gbps and report sustained rate, BER and eye margin",
+                    "requestBody": json_content(benchmark_request),
+                    "responses": {
+                        "200": ok("Benchmark summary", benchmark_report),
+                        "400": error_response("Invalid steps or step_ms"),
+                        "404": error_response("Unknown corridor"),
+                        "409": error_response("Corridor is not Active")
+                    }
+                }
+            },
+            "/v1/topology/capacity": {
+                "get": {
+                    "summary": "Free grid slots in a band and how many more corridors of a given width fit",
+                    "parameters": [
+                        {"name": "band", "in": "query", "required": true, "schema": {"type": "string", "enum": ["O", "E", "S", "C", "L"]}},
+                        {"name": "spacing_ghz", "in": "query", "description": "Grid spacing (default 100)", "schema": {"type": "number"}},
+                        {"name": "lanes", "in": "query", "description": "Corridor width in lanes (default 1)", "schema": {"type": "integer"}}
+                    ],
+                    "responses": {
+                        "200": ok("Grid capacity", capacity),
+                        "400": error_response("Unknown band, or non-positive spacing or lanes")
+                    }
+                }
+            },
+            "/v1/capabilities": {
+                "get": {
+                    "summary": "Corridor types, modulation formats, lane rates, bands and reach limits this daemon supports",
+                    "responses": {"200": ok("Capabilities", capabilities)}
+                }
+            },
+            "/v1/pubkey": {
+                "get": {
+                    "summary": "Key and claims version for verifying allocation receipts",
+                    "responses": {"200": ok("Receipt verification key", json!({
+                        "type": "object",
+                        "properties": {
+                            "alg": {"type": "string", "enum": ["ed25519"]},
+                            "public_key": {"type": "string", "description": "Hex-encoded ed25519 public key"},
+                            "receipt_version": {"type": "integer"}
+                        }
+                    }))}
+                }
+            },
+            "/v1/alerts/ws": {
+                "get": {
+                    "summary": "WebSocket of fleet alerts as they fire, one JSON frame per alert",
+                    "responses": {"101": {"description": "Switching to the WebSocket protocol"}}
+                }
+            },
+            "/v1/selftest": {
+                "get": {
+                    "summary": "Dry-run allocation, dependency probes and a metrics encode, each timed",
+                    "responses": {
+                        "200": ok("Every required check passed", selftest.clone()),
+                        "503": ok("A required check failed", selftest)
+                    }
+                }
+            },
+            "/v1/version": {
+                "get": {
+                    "summary": "Version, git sha and build time of the running corrd",
+                    "responses": {"200": ok("Build metadata", json!({
+                        "type": "object",
+                        "properties": {
+                            "version": {"type": "string"},
+                            "git_sha": {"type": "string"},
+                            "built_at": {"type": "string", "format": "date-time"}
+                        }
+                    }))}
+                }
+            },
+            "/health": {
+                "get": {
+                    "summary": "Liveness; always ok while the process serves requests",
+                    "responses": {"200": ok("Alive", json!({"type": "object", "properties": {"status": {"type": "string", "enum": ["ok"]}}}))}
+                }
+            },
+            "/health/ready": {
+                "get": {
+                    "summary": "Probe HELIOPASS (optional) and attestd (required)",
+                    "responses": {
+                        "200": ok("Every required dependency is reachable", dependency_health.clone()),
+                        "503": ok("A required dependency is unreachable", dependency_health)
+                    }
+                }
+            },
+            "/ready": {
+                "get": {
+                    "summary": "Readiness from rolling dependency latency; degraded once a p95 exceeds CORRD_DEGRADED_P95_MS",
+                    "responses": {"200": ok("Readiness", json!({
+                        "type": "object",
+                        "properties": {
+                            "status": {"type": "string", "enum": ["ready", "degraded"]},
+                            "dependencies": {"type": "object", "additionalProperties": latency_summary}
+                        }
+                    }))}
+                }
+            },
+            "/metrics": {
+                "get": {
+                    "summary": "Prometheus exposition; protobuf when Accept prefers it, gzip when Accept-Encoding allows",
+                    "responses": {
+                        "200": {
+                            "description": "Metrics",
+                            "content": {
+                                "text/plain": {"schema": {"type": "string"}},
+                                "application/vnd.google.protobuf": {"schema": {"type": "string", "format": "binary"}}
+                            }
+                        },
+                        "401": error_response("CORRD_METRICS_TOKEN is set and the bearer token is missing or wrong")
+                    }
+                }
+            },
+            "/openapi.json": {
+                "get": {
+                    "summary": "This document",
+                    "responses": {"200": ok("OpenAPI 3.0 document", json!({"type": "object"}))}
+                }
+            }
+        },
+        "components": {"schemas": schemas}
+    })
+}
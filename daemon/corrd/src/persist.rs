@@ -0,0 +1,58 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+
+use crate::Corridor;
+
+/// JSON snapshot of the corridor map at `CORRD_STATE_PATH`. Archived corridors
+/// are stored in the same list with status `Decommissioned`.
+pub struct StateStore {
+    path: PathBuf,
+}
+
+impl StateStore {
+    pub fn from_env() -> Option<Self> {
+        env::var("CORRD_STATE_PATH").ok().filter(|p| !p.is_empty()).map(|p| Self { path: PathBuf::from(p) })
+    }
+
+    /// Load the saved corridors; a missing or malformed file yields an empty map.
+    pub fn load(&self) -> HashMap<String, Corridor> {
+        let text = match std::fs::read_to_string(&self.path) {
+            Ok(t) => t,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return HashMap::new(),
+            Err(e) => {
+                tracing::warn!("cannot read state file {}: {}, starting empty", self.path.display(), e);
+                return HashMap::new();
+            }
+        };
+        match serde_json::from_str::<Vec<Corridor>>(&text) {
+            Ok(list) => list.into_iter().map(|c| (c.id.clone(), c)).collect(),
+            Err(e) => {
+                tracing::warn!("malformed state file {}: {}, starting empty", self.path.display(), e);
+                HashMap::new()
+            }
+        }
+    }
+
+    /// Write the map to a temp file next to the target and rename it into place,
+    /// so a crash mid-write leaves the previous snapshot intact.
+    pub fn save<'a>(&self, corridors: impl Iterator<Item = &'a Corridor>) -> Result<()> {
+        let mut list: Vec<&Corridor> = corridors.collect();
+        list.sort_by(|a, b| a.id.cmp(&b.id));
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(&list)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Next numeric id after the highest `cor-<hex>` id present.
+pub fn next_id_after(corridors: &HashMap<String, Corridor>) -> u64 {
+    corridors
+        .keys()
+        .filter_map(|id| id.strip_prefix("cor-").and_then(|h| u64::from_str_radix(h, 16).ok()))
+        .max()
+        .map(|max| max + 1)
+        .unwrap_or(1)
+}
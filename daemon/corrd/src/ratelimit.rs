@@ -0,0 +1,159 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::env;
+use std::hash::{Hash, Hasher};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use warp::http::Method;
+use warp::path::FullPath;
+use warp::Filter;
+
+use crate::auth::AuthConfig;
+use crate::error::CorridorError;
+
+/// Buckets tracked before idle, refilled ones are dropped.
+const PRUNE_ABOVE: usize = 4096;
+
+/// The connection's peer, put in request extensions by the accept loop since
+/// `warp::addr::remote` is empty under a hand-built hyper server.
+#[derive(Debug, Clone, Copy)]
+pub struct PeerAddr(pub SocketAddr);
+
+/// Wrap a per-connection service so each request carries its `PeerAddr`.
+pub fn with_peer<S>(svc: S, peer: SocketAddr) -> impl hyper::service::Service<
+    hyper::Request<hyper::Body>,
+    Response = hyper::Response<hyper::Body>,
+    Error = Infallible,
+    Future = S::Future,
+>
+where
+    S: hyper::service::Service<hyper::Request<hyper::Body>, Response = hyper::Response<hyper::Body>, Error = Infallible>
+        + Clone,
+{
+    hyper::service::service_fn(move |mut req: hyper::Request<hyper::Body>| {
+        req.extensions_mut().insert(PeerAddr(peer));
+        svc.clone().call(req)
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Limit {
+    per_sec: f64,
+    burst: f64,
+}
+
+impl Limit {
+    /// `None` when `{prefix}_RPS` is 0, which turns the limit off.
+    fn from_env(prefix: &str, per_sec: f64, burst: f64) -> Option<Self> {
+        let get = |key: String, default: f64| env::var(key).ok().and_then(|v| v.parse().ok()).unwrap_or(default);
+        let per_sec = get(format!("{}_RPS", prefix), per_sec);
+        let burst = get(format!("{}_BURST", prefix), burst).max(1.0);
+        (per_sec > 0.0).then_some(Self { per_sec, burst })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Class {
+    Write,
+    Read,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Per-client token buckets: mutating requests draw on `CORRD_RATE_LIMIT_RPS`/`_BURST`
+/// (default 5/s, burst 20), reads and `/metrics` on the looser
+/// `CORRD_READ_RATE_LIMIT_RPS`/`_BURST` (default 50/s, burst 100). `/health` and
+/// `/ready` are never limited.
+pub struct RateLimiter {
+    write: Option<Limit>,
+    read: Option<Limit>,
+    buckets: Mutex<HashMap<(Class, String), Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn from_env() -> Self {
+        Self {
+            write: Limit::from_env("CORRD_RATE_LIMIT", 5.0, 20.0),
+            read: Limit::from_env("CORRD_READ_RATE_LIMIT", 50.0, 100.0),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn limit_for(&self, class: Class) -> Option<Limit> {
+        match class {
+            Class::Write => self.write,
+            Class::Read => self.read,
+        }
+    }
+
+    /// Take a token for `client`, or return the whole seconds until one is available.
+    fn take(&self, class: Class, client: String) -> Result<(), u64> {
+        let Some(limit) = self.limit_for(class) else { return Ok(()) };
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() > PRUNE_ABOVE {
+            buckets.retain(|(class, _), b| {
+                self.limit_for(*class)
+                    .is_some_and(|l| now.duration_since(b.updated).as_secs_f64() * l.per_sec < l.burst)
+            });
+        }
+        let bucket = buckets.entry((class, client)).or_insert(Bucket { tokens: limit.burst, updated: now });
+        let refill = now.duration_since(bucket.updated).as_secs_f64() * limit.per_sec;
+        bucket.tokens = (bucket.tokens + refill).min(limit.burst);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - bucket.tokens) / limit.per_sec).ceil().max(1.0) as u64)
+        }
+    }
+}
+
+fn class_for(method: &Method, path: &str) -> Option<Class> {
+    if path == "/health" || path.starts_with("/health/") || path == "/ready" {
+        None
+    } else if method == Method::GET || method == Method::HEAD || method == Method::OPTIONS {
+        Some(Class::Read)
+    } else {
+        Some(Class::Write)
+    }
+}
+
+/// Clients are told apart by bearer token when auth is on and the token checked out
+/// (hashed, so the map holds no secrets), otherwise by peer IP. Unverified tokens
+/// are ignored so a fresh random one per request can't buy a fresh bucket.
+fn client_key(verified_token: Option<&str>, peer: Option<PeerAddr>) -> String {
+    match verified_token {
+        Some(token) => {
+            let mut hasher = DefaultHasher::new();
+            token.hash(&mut hasher);
+            format!("token:{:016x}", hasher.finish())
+        }
+        None => peer.map(|p| p.0.ip().to_string()).unwrap_or_else(|| "unknown".to_string()),
+    }
+}
+
+/// Filter placed after `auth::authorize`; rejects with `CorridorError::RateLimited`
+/// once the caller's bucket for the request's class is empty.
+pub fn limit(limiter: Arc<RateLimiter>, auth: Arc<AuthConfig>) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::method()
+        .and(warp::path::full())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::ext::optional::<PeerAddr>())
+        .and_then(move |method: Method, path: FullPath, header: Option<String>, peer: Option<PeerAddr>| {
+            let (limiter, config) = (limiter.clone(), auth.clone());
+            async move {
+                let Some(class) = class_for(&method, path.as_str()) else { return Ok(()) };
+                limiter
+                    .take(class, client_key(config.verified_token(path.as_str(), header.as_deref()), peer))
+                    .map_err(|retry_after| warp::reject::custom(CorridorError::RateLimited(retry_after)))
+            }
+        })
+        .untuple_one()
+}
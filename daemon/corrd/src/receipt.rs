@@ -0,0 +1,90 @@
+use anyhow::Result;
+use ed25519_dalek::{Signer, SigningKey};
+use serde::Serialize;
+use std::env;
+
+use crate::{Corridor, CorridorType, QoSSettings};
+
+/// Claims version new receipts are signed at, also reported by `GET /v1/pubkey`.
+pub const RECEIPT_VERSION: u32 = 2;
+
+/// Fields covered by an allocation receipt, serialized as JSON in this order.
+/// Auditors rebuild the same object from a `Corridor` response to check the signature.
+///
+/// Version 2 leads with `"v": 2` and signs the snake_case `corridor_type`
+/// (`si_corridor`). Version 1 receipts, issued before the wire names changed, have
+/// no `v` and sign `SiCorridor`/`CarbonCorridor`; rebuild those without `v` and
+/// with the legacy name. Existing receipts are never re-signed on restart.
+#[derive(Serialize)]
+struct ReceiptClaims<'a> {
+    v: u32,
+    id: &'a str,
+    corridor_type: &'a CorridorType,
+    lanes: u32,
+    lambda_nm: &'a [u32],
+    /// Omitted when unset so receipts from before λ groups still verify.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lambdas_per_lane: Option<u32>,
+    min_gbps: u32,
+    latency_budget_ns: u32,
+    reach_mm: u32,
+    mode: &'a str,
+    qos: &'a QoSSettings,
+    achievable_gbps: u32,
+    created_at: &'a chrono::DateTime<chrono::Utc>,
+}
+
+fn claims_bytes(c: &Corridor) -> Vec<u8> {
+    let claims = ReceiptClaims {
+        v: RECEIPT_VERSION,
+        id: &c.id,
+        corridor_type: &c.corridor_type,
+        lanes: c.lanes,
+        lambda_nm: &c.lambda_nm,
+        lambdas_per_lane: c.lambdas_per_lane,
+        min_gbps: c.min_gbps,
+        latency_budget_ns: c.latency_budget_ns,
+        reach_mm: c.reach_mm,
+        mode: &c.mode,
+        qos: &c.qos,
+        achievable_gbps: c.achievable_gbps,
+        created_at: &c.created_at,
+    };
+    serde_json::to_vec(&claims).unwrap_or_default()
+}
+
+/// Signs allocation receipts with the daemon's ed25519 key.
+pub struct ReceiptSigner {
+    key: SigningKey,
+}
+
+impl ReceiptSigner {
+    /// Load a hex-encoded 32-byte seed from `CORRD_RECEIPT_KEY_PATH`, or generate an
+    /// ephemeral key (receipts then only verify for the lifetime of this process).
+    pub fn from_env() -> Result<Self> {
+        match env::var("CORRD_RECEIPT_KEY_PATH") {
+            Ok(path) => {
+                let text = std::fs::read_to_string(&path)
+                    .map_err(|e| anyhow::anyhow!("read receipt key {} failed: {}", path, e))?;
+                let seed: [u8; 32] = hex::decode(text.trim())
+                    .ok()
+                    .and_then(|b| b.try_into().ok())
+                    .ok_or_else(|| anyhow::anyhow!("receipt key {} must be 64 hex characters", path))?;
+                Ok(Self { key: SigningKey::from_bytes(&seed) })
+            }
+            Err(_) => {
+                tracing::warn!("CORRD_RECEIPT_KEY_PATH not set, signing receipts with an ephemeral key");
+                Ok(Self { key: SigningKey::from_bytes(&rand::random::<[u8; 32]>()) })
+            }
+        }
+    }
+
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.key.verifying_key().as_bytes())
+    }
+
+    /// Detached hex signature over the corridor's receipt claims.
+    pub fn sign(&self, corridor: &Corridor) -> String {
+        hex::encode(self.key.sign(&claims_bytes(corridor)).to_bytes())
+    }
+}
@@ -0,0 +1,109 @@
+use bytes::{Buf, BufMut};
+use futures_util::TryStreamExt;
+use serde::de::DeserializeOwned;
+use std::convert::Infallible;
+use warp::http::StatusCode;
+use warp::{Filter, Reply};
+
+use crate::error::CorridorError;
+
+/// A JSON body that failed to deserialize, with the path of the offending field.
+#[derive(Debug)]
+pub struct InvalidBody {
+    pub error: String,
+    pub field: String,
+}
+
+impl warp::reject::Reject for InvalidBody {}
+
+/// A request body longer than `CORRD_MAX_BODY_BYTES`.
+#[derive(Debug)]
+pub struct BodyTooLarge {
+    pub limit: u64,
+}
+
+impl warp::reject::Reject for BodyTooLarge {}
+
+/// Largest request body accepted, from `CORRD_MAX_BODY_BYTES` (default 64 KiB).
+fn max_body_bytes() -> u64 {
+    std::env::var("CORRD_MAX_BODY_BYTES").ok().and_then(|v| v.parse().ok()).unwrap_or(64 * 1024)
+}
+
+/// Read the body, rejecting with 413 as soon as it passes `limit` bytes. The
+/// limit is enforced on the stream itself, so chunked and h2 bodies without a
+/// `Content-Length` are bounded too.
+fn limited_body(limit: u64) -> impl Filter<Extract = (bytes::Bytes,), Error = warp::Rejection> + Clone {
+    warp::header::optional::<u64>("content-length")
+        .and(warp::body::stream())
+        .and_then(move |declared, stream| read_limited(declared, stream, limit))
+}
+
+async fn read_limited<S, B>(declared: Option<u64>, stream: S, limit: u64) -> Result<bytes::Bytes, warp::Rejection>
+where
+    S: futures_util::Stream<Item = Result<B, warp::Error>>,
+    B: Buf,
+{
+    if declared.is_some_and(|len| len > limit) {
+        return Err(warp::reject::custom(BodyTooLarge { limit }));
+    }
+    futures_util::pin_mut!(stream);
+    let mut body = bytes::BytesMut::new();
+    while let Some(chunk) = stream
+        .try_next()
+        .await
+        .map_err(|e| warp::reject::custom(InvalidBody { error: e.to_string(), field: ".".to_string() }))?
+    {
+        if body.len() as u64 + chunk.remaining() as u64 > limit {
+            return Err(warp::reject::custom(BodyTooLarge { limit }));
+        }
+        body.put(chunk);
+    }
+    Ok(body.freeze())
+}
+
+/// Drop-in for `warp::body::json()` that records which field failed to parse.
+/// Bodies over `CORRD_MAX_BODY_BYTES` are rejected with 413.
+pub fn json_body<T: DeserializeOwned + Send>() -> impl Filter<Extract = (T,), Error = warp::Rejection> + Clone {
+    limited_body(max_body_bytes()).and_then(|body: bytes::Bytes| async move {
+        let de = &mut serde_json::Deserializer::from_slice(&body);
+        serde_path_to_error::deserialize(de).map_err(|e| {
+            let field = e.path().to_string();
+            warp::reject::custom(InvalidBody { error: e.into_inner().to_string(), field })
+        })
+    })
+}
+
+/// Render rejections as `{"error": ...}` JSON instead of warp's plain-text bodies.
+/// 429s also carry `Retry-After`.
+pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply, Infallible> {
+    let (code, body) = if let Some(e) = err.find::<CorridorError>() {
+        (e.status(), serde_json::json!({"error": e.to_string(), "code": e.code()}))
+    } else if let Some(e) = err.find::<InvalidBody>() {
+        (StatusCode::BAD_REQUEST, serde_json::json!({"error": e.error, "field": e.field}))
+    } else if err.is_not_found() {
+        (StatusCode::NOT_FOUND, serde_json::json!({"error": "not found"}))
+    } else if let Some(e) = err.find::<warp::filters::body::BodyDeserializeError>() {
+        (StatusCode::BAD_REQUEST, serde_json::json!({"error": e.to_string()}))
+    } else if let Some(e) = err.find::<warp::reject::InvalidQuery>() {
+        (StatusCode::BAD_REQUEST, serde_json::json!({"error": e.to_string()}))
+    } else if let Some(e) = err.find::<warp::reject::MissingHeader>() {
+        (StatusCode::BAD_REQUEST, serde_json::json!({"error": e.to_string()}))
+    } else if let Some(e) = err.find::<warp::reject::InvalidHeader>() {
+        (StatusCode::BAD_REQUEST, serde_json::json!({"error": e.to_string()}))
+    } else if let Some(e) = err.find::<warp::reject::UnsupportedMediaType>() {
+        (StatusCode::UNSUPPORTED_MEDIA_TYPE, serde_json::json!({"error": e.to_string()}))
+    } else if let Some(e) = err.find::<BodyTooLarge>() {
+        (StatusCode::PAYLOAD_TOO_LARGE, serde_json::json!({"error": format!("request body exceeds {} bytes", e.limit)}))
+    } else if let Some(e) = err.find::<warp::reject::MethodNotAllowed>() {
+        // Checked last: other routes sharing the path add it alongside the real cause.
+        (StatusCode::METHOD_NOT_ALLOWED, serde_json::json!({"error": e.to_string()}))
+    } else {
+        tracing::warn!("unhandled rejection: {:?}", err);
+        (StatusCode::INTERNAL_SERVER_ERROR, serde_json::json!({"error": "internal error"}))
+    };
+    let mut response = warp::reply::with_status(warp::reply::json(&body), code).into_response();
+    if let Some(CorridorError::RateLimited(secs)) = err.find::<CorridorError>() {
+        response.headers_mut().insert(warp::http::header::RETRY_AFTER, (*secs).into());
+    }
+    Ok(response)
+}
@@ -0,0 +1,116 @@
+use anyhow::Result;
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::{ClientConfig, RootCertStore, ServerConfig, SupportedProtocolVersion};
+use std::env;
+use std::sync::Arc;
+
+/// Lowest TLS protocol version the daemon will negotiate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TlsMinVersion {
+    Tls12,
+    Tls13,
+}
+
+/// Minimum TLS version and approved cipher suites, applied to the server listener
+/// and outbound HELIOPASS/attestd clients.
+#[derive(Debug, Clone)]
+pub struct TlsPolicy {
+    pub min_version: TlsMinVersion,
+    /// rustls suite names such as `TLS13_AES_256_GCM_SHA384`; `None` keeps the defaults.
+    pub cipher_suites: Option<Vec<String>>,
+}
+
+impl TlsPolicy {
+    /// Read `CORRD_TLS_MIN_VERSION` (`1.2` default, or `1.3`) and `CORRD_TLS_CIPHERS`.
+    pub fn from_env() -> Result<Self> {
+        let min_version = match env::var("CORRD_TLS_MIN_VERSION").as_deref() {
+            Err(_) | Ok("1.2") => TlsMinVersion::Tls12,
+            Ok("1.3") => TlsMinVersion::Tls13,
+            Ok(other) => {
+                return Err(anyhow::anyhow!("unsupported CORRD_TLS_MIN_VERSION '{}', expected 1.2 or 1.3", other))
+            }
+        };
+        let cipher_suites = env::var("CORRD_TLS_CIPHERS").ok().map(|v| {
+            v.split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect()
+        });
+        Ok(Self { min_version, cipher_suites })
+    }
+
+    pub fn protocol_versions(&self) -> &'static [&'static SupportedProtocolVersion] {
+        static TLS12_AND_UP: [&SupportedProtocolVersion; 2] = [&rustls::version::TLS13, &rustls::version::TLS12];
+        static TLS13_ONLY: [&SupportedProtocolVersion; 1] = [&rustls::version::TLS13];
+        match self.min_version {
+            TlsMinVersion::Tls12 => &TLS12_AND_UP,
+            TlsMinVersion::Tls13 => &TLS13_ONLY,
+        }
+    }
+
+    /// Crypto provider restricted to the approved cipher suites.
+    pub fn crypto_provider(&self) -> Result<CryptoProvider> {
+        let mut provider = rustls::crypto::ring::default_provider();
+        if let Some(allowed) = &self.cipher_suites {
+            provider.cipher_suites.retain(|s| allowed.contains(&format!("{:?}", s.suite())));
+            if provider.cipher_suites.is_empty() {
+                return Err(anyhow::anyhow!("CORRD_TLS_CIPHERS matches no supported cipher suite"));
+            }
+        }
+        Ok(provider)
+    }
+}
+
+/// Server TLS config from `CORRD_TLS_CERT` / `CORRD_TLS_KEY` (PEM), or `None` for plaintext.
+pub fn server_config_from_env(policy: &TlsPolicy) -> Result<Option<Arc<ServerConfig>>> {
+    let (cert_path, key_path) = match (env::var("CORRD_TLS_CERT"), env::var("CORRD_TLS_KEY")) {
+        (Ok(c), Ok(k)) => (c, k),
+        (Err(_), Err(_)) => return Ok(None),
+        _ => return Err(anyhow::anyhow!("CORRD_TLS_CERT and CORRD_TLS_KEY must be set together")),
+    };
+    let certs = load_certs(&cert_path)?;
+    let key = load_key(&key_path)?;
+    let mut config = ServerConfig::builder_with_provider(Arc::new(policy.crypto_provider()?))
+        .with_protocol_versions(policy.protocol_versions())?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(Some(Arc::new(config)))
+}
+
+fn load_certs(path: &str) -> Result<Vec<CertificateDer<'static>>> {
+    let file = std::fs::File::open(path).map_err(|e| anyhow::anyhow!("open {} failed: {}", path, e))?;
+    rustls_pemfile::certs(&mut std::io::BufReader::new(file))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| anyhow::anyhow!("read {} failed: {}", path, e))
+}
+
+fn load_key(path: &str) -> Result<PrivateKeyDer<'static>> {
+    let file = std::fs::File::open(path).map_err(|e| anyhow::anyhow!("open {} failed: {}", path, e))?;
+    rustls_pemfile::private_key(&mut std::io::BufReader::new(file))
+        .map_err(|e| anyhow::anyhow!("read {} failed: {}", path, e))?
+        .ok_or_else(|| anyhow::anyhow!("no private key found in {}", path))
+}
+
+/// Client TLS config under `policy`. `ca_path` (PEM) replaces the public web roots
+/// when set; `identity` is a (certificate, key) PEM pair presented for mutual TLS.
+pub fn client_config(policy: &TlsPolicy, ca_path: Option<&str>, identity: Option<(&str, &str)>) -> Result<ClientConfig> {
+    let roots = match ca_path {
+        Some(path) => {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(path)? {
+                roots.add(cert).map_err(|e| anyhow::anyhow!("invalid CA certificate in {}: {}", path, e))?;
+            }
+            if roots.is_empty() {
+                return Err(anyhow::anyhow!("no CA certificates found in {}", path));
+            }
+            roots
+        }
+        None => RootCertStore { roots: webpki_roots::TLS_SERVER_ROOTS.to_vec() },
+    };
+    let builder = ClientConfig::builder_with_provider(Arc::new(policy.crypto_provider()?))
+        .with_protocol_versions(policy.protocol_versions())?
+        .with_root_certificates(roots);
+    Ok(match identity {
+        Some((cert_path, key_path)) => builder.with_client_auth_cert(load_certs(cert_path)?, load_key(key_path)?)?,
+        None => builder.with_no_client_auth(),
+    })
+}
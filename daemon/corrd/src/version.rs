@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// What `GET /v1/version` reports; the sha and timestamp come from `build.rs`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_sha: &'static str,
+    pub built_at: DateTime<Utc>,
+}
+
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_sha: env!("CORRD_GIT_SHA"),
+        built_at: env!("CORRD_BUILT_AT").parse().ok().and_then(|s| DateTime::from_timestamp(s, 0)).unwrap_or_default(),
+    }
+}
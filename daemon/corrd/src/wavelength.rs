@@ -0,0 +1,114 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Speed of light expressed in nm·THz, so `f_thz = C_NM_THZ / lambda_nm`.
+const C_NM_THZ: f64 = 299_792.458;
+
+/// ITU-T G.694.1 DWDM grid: channels at 193.1 THz + n × 100 GHz.
+const ITU_ANCHOR_THZ: f64 = 193.1;
+const ITU_SPACING_THZ: f64 = 0.1;
+
+/// Wavelengths are whole nanometres, so one counts as on a channel when it is
+/// within this of the centre: about a quarter of the ~0.8 nm spacing near 1550 nm.
+const CHANNEL_TOLERANCE_NM: f64 = 0.2;
+
+/// Standard optical telecom bands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub enum Band {
+    O,
+    E,
+    S,
+    C,
+    L,
+}
+
+impl Band {
+    pub const ALL: [Band; 5] = [Band::O, Band::E, Band::S, Band::C, Band::L];
+
+    /// Inclusive lower / exclusive upper wavelength bound in nm.
+    pub fn range_nm(self) -> (u32, u32) {
+        match self {
+            Band::O => (1260, 1360),
+            Band::E => (1360, 1460),
+            Band::S => (1460, 1530),
+            Band::C => (1530, 1565),
+            Band::L => (1565, 1625),
+        }
+    }
+
+    fn contains(self, nm: u32) -> bool {
+        let (lo, hi) = self.range_nm();
+        nm >= lo && nm < hi
+    }
+
+    /// Frequency span (low, high) of the band in THz.
+    fn span_thz(self) -> (f64, f64) {
+        let (lo, hi) = self.range_nm();
+        (C_NM_THZ / hi as f64, C_NM_THZ / lo as f64)
+    }
+}
+
+/// The band `nm` falls in, if any.
+pub fn band_of(nm: u32) -> Option<Band> {
+    Band::ALL.into_iter().find(|b| b.contains(nm))
+}
+
+/// Centre wavelength (nm) of the ITU 100 GHz channel closest to `nm`.
+pub fn nearest_channel_nm(nm: u32) -> f64 {
+    let n = ((C_NM_THZ / nm as f64 - ITU_ANCHOR_THZ) / ITU_SPACING_THZ).round();
+    C_NM_THZ / (ITU_ANCHOR_THZ + n * ITU_SPACING_THZ)
+}
+
+/// Whether `nm` is inside a band and within `CHANNEL_TOLERANCE_NM` of an ITU
+/// 100 GHz channel centre, e.g. 1550 (193.4 THz, 1550.12 nm) but not 1552.
+pub fn is_valid_channel(nm: u32) -> bool {
+    band_of(nm).is_some() && (nearest_channel_nm(nm) - nm as f64).abs() <= CHANNEL_TOLERANCE_NM
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CapacityReport {
+    pub band: Band,
+    pub spacing_ghz: f64,
+    pub lanes: u32,
+    pub total_slots: u32,
+    pub used_slots: u32,
+    pub free_slots: u32,
+    pub corridors_fit: u32,
+}
+
+/// Fleet view of which grid slots are claimed, built from allocated wavelength plans.
+pub struct LambdaAllocator {
+    claimed_nm: Vec<u32>,
+}
+
+impl LambdaAllocator {
+    pub fn from_plans<'a>(plans: impl IntoIterator<Item = &'a [u32]>) -> Self {
+        Self { claimed_nm: plans.into_iter().flatten().copied().collect() }
+    }
+
+    /// How many corridors of `lanes` wavelengths still fit in `band` on a `spacing_ghz` grid.
+    pub fn capacity(&self, band: Band, spacing_ghz: f64, lanes: u32) -> CapacityReport {
+        let (f_lo, f_hi) = band.span_thz();
+        let spacing_thz = spacing_ghz / 1000.0;
+        let total_slots = ((f_hi - f_lo) / spacing_thz).floor() as u32;
+        let used: HashSet<u32> = self
+            .claimed_nm
+            .iter()
+            .filter(|nm| band.contains(**nm))
+            .map(|nm| (((C_NM_THZ / *nm as f64) - f_lo) / spacing_thz).floor() as u32)
+            .filter(|slot| *slot < total_slots)
+            .collect();
+        let used_slots = used.len() as u32;
+        let free_slots = total_slots.saturating_sub(used_slots);
+        CapacityReport {
+            band,
+            spacing_ghz,
+            lanes,
+            total_slots,
+            used_slots,
+            free_slots,
+            corridors_fit: free_slots / lanes.max(1),
+        }
+    }
+}
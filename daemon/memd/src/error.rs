@@ -0,0 +1,32 @@
+use warp::http::StatusCode;
+
+/// Service errors with a fixed HTTP mapping. Service methods still return
+/// `anyhow::Result`; anything that isn't one of these is treated as a bad request.
+#[derive(Debug, thiserror::Error)]
+pub enum FfmError {
+    #[error("{0}")]
+    InvalidRequest(String),
+    #[error("{0}")]
+    InsufficientCapacity(String),
+    #[error("Allocation {0} not found")]
+    NotFound(String),
+}
+
+impl FfmError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            FfmError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            FfmError::InsufficientCapacity(_) => StatusCode::INSUFFICIENT_STORAGE,
+            FfmError::NotFound(_) => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+impl From<anyhow::Error> for FfmError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<FfmError>() {
+            Ok(err) => err,
+            Err(e) => FfmError::InvalidRequest(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,304 @@
+mod error;
+
+use anyhow::Result;
+use prometheus::{Encoder, IntGaugeVec, Opts, TextEncoder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use warp::Filter;
+
+use error::FfmError;
+
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfmAllocateRequest {
+    pub bytes: u64,
+    pub latency_class: String,
+    pub bandwidth_floor_GBs: u64,
+    pub persistence: String,
+    #[serde(default)]
+    pub shareable: bool,
+    pub security_domain: String,
+    #[serde(default)]
+    pub attestation_required: Option<bool>,
+    #[serde(default)]
+    pub attestation_ticket: Option<String>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FfmHandle {
+    pub id: String,
+    pub bytes: u64,
+    pub latency_class: String,
+    pub bandwidth_floor_GBs: u64,
+    pub persistence: String,
+    pub shareable: bool,
+    pub security_domain: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Latency classes from `ffm_policy.yaml` and the bandwidth each tier can sustain (GB/s).
+const TIERS: &[(&str, u64)] = &[("T0", 1000), ("T1", 200), ("T2", 150), ("T3", 20)];
+
+const PERSISTENCE_MODES: &[&str] = &["none", "session", "durable"];
+
+/// Per-`security_domain` ceilings: `MEMD_DOMAIN_LIMIT_BYTES` applies to every
+/// domain, and `MEMD_DOMAIN_LIMITS` (`tenant-a=1073741824,tenant-b=...`) overrides
+/// it for named ones. Domains with neither are only bound by total capacity.
+#[derive(Debug, Clone, Default)]
+struct DomainLimits {
+    default: Option<u64>,
+    overrides: HashMap<String, u64>,
+}
+
+impl DomainLimits {
+    fn from_env() -> Result<Self> {
+        let default = match env::var("MEMD_DOMAIN_LIMIT_BYTES") {
+            Ok(v) => Some(v.parse().map_err(|e| anyhow::anyhow!("invalid MEMD_DOMAIN_LIMIT_BYTES '{}': {}", v, e))?),
+            Err(_) => None,
+        };
+        let mut overrides = HashMap::new();
+        for entry in env::var("MEMD_DOMAIN_LIMITS").unwrap_or_default().split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (domain, bytes) = entry
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("invalid MEMD_DOMAIN_LIMITS entry '{}', expected domain=bytes", entry))?;
+            let bytes = bytes
+                .trim()
+                .parse()
+                .map_err(|e| anyhow::anyhow!("invalid MEMD_DOMAIN_LIMITS entry '{}': {}", entry, e))?;
+            overrides.insert(domain.trim().to_string(), bytes);
+        }
+        Ok(Self { default, overrides })
+    }
+
+    fn for_domain(&self, domain: &str) -> Option<u64> {
+        self.overrides.get(domain).copied().or(self.default)
+    }
+}
+
+pub struct FfmService {
+    allocations: Arc<RwLock<HashMap<String, FfmHandle>>>,
+    next_id: Arc<RwLock<u32>>,
+    capacity_bytes: u64,
+    domain_limits: DomainLimits,
+    registry: prometheus::Registry,
+    m_domain_allocated: IntGaugeVec,
+    m_domain_limit: IntGaugeVec,
+}
+
+impl FfmService {
+    /// Total capacity comes from `MEMD_CAPACITY_BYTES` (default 1 TiB); per-domain
+    /// ceilings are described on `DomainLimits`.
+    pub fn new() -> Result<Self> {
+        let capacity_bytes = env::var("MEMD_CAPACITY_BYTES").ok().and_then(|v| v.parse().ok()).unwrap_or(1u64 << 40);
+        let domain_limits = DomainLimits::from_env()?;
+        let registry = prometheus::Registry::new();
+        let m_domain_allocated = IntGaugeVec::new(
+            Opts::new("ffm_domain_bytes_allocated", "Bytes currently allocated per security domain"),
+            &["security_domain"],
+        )?;
+        let m_domain_limit = IntGaugeVec::new(
+            Opts::new("ffm_domain_bytes_limit", "Allocation ceiling per security domain (bytes), where one is set"),
+            &["security_domain"],
+        )?;
+        registry.register(Box::new(m_domain_allocated.clone()))?;
+        registry.register(Box::new(m_domain_limit.clone()))?;
+        for (domain, limit) in &domain_limits.overrides {
+            m_domain_limit.with_label_values(&[domain]).set(*limit as i64);
+        }
+        Ok(Self {
+            allocations: Arc::new(RwLock::new(HashMap::new())),
+            next_id: Arc::new(RwLock::new(1)),
+            capacity_bytes,
+            domain_limits,
+            registry,
+            m_domain_allocated,
+            m_domain_limit,
+        })
+    }
+
+    /// Refresh both gauges for `domain`; call with the allocations lock held.
+    fn update_domain_metrics(&self, allocations: &HashMap<String, FfmHandle>, domain: &str) {
+        self.m_domain_allocated.with_label_values(&[domain]).set(domain_bytes(allocations, domain) as i64);
+        if let Some(limit) = self.domain_limits.for_domain(domain) {
+            self.m_domain_limit.with_label_values(&[domain]).set(limit as i64);
+        }
+    }
+
+    pub fn metrics(&self) -> Result<String> {
+        let mut buf = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buf)?;
+        Ok(String::from_utf8(buf)?)
+    }
+
+    fn check_request(req: &FfmAllocateRequest) -> Result<()> {
+        if req.bytes == 0 {
+            return Err(anyhow::anyhow!("bytes must be positive"));
+        }
+        let (_, tier_gbs) = TIERS
+            .iter()
+            .find(|(name, _)| *name == req.latency_class)
+            .ok_or_else(|| anyhow::anyhow!("unknown latency_class '{}', expected T0-T3", req.latency_class))?;
+        if req.bandwidth_floor_GBs > *tier_gbs {
+            return Err(anyhow::anyhow!(
+                "bandwidth_floor_GBs {} exceeds the {} limit of {} GB/s",
+                req.bandwidth_floor_GBs, req.latency_class, tier_gbs
+            ));
+        }
+        if !PERSISTENCE_MODES.contains(&req.persistence.as_str()) {
+            return Err(anyhow::anyhow!(
+                "unknown persistence '{}', expected one of: {}",
+                req.persistence, PERSISTENCE_MODES.join(", ")
+            ));
+        }
+        if req.security_domain.is_empty() {
+            return Err(anyhow::anyhow!("security_domain must not be empty"));
+        }
+        if req.attestation_required.unwrap_or(false) {
+            return Err(anyhow::anyhow!("attestation is not supported by memd"));
+        }
+        Ok(())
+    }
+
+    pub async fn allocate(&self, req: FfmAllocateRequest) -> Result<FfmHandle> {
+        Self::check_request(&req)?;
+        let mut allocations = self.allocations.write().await;
+        let used: u64 = allocations.values().map(|h| h.bytes).sum();
+        if used.saturating_add(req.bytes) > self.capacity_bytes {
+            return Err(FfmError::InsufficientCapacity(format!(
+                "insufficient capacity: {} bytes requested, {} of {} bytes free",
+                req.bytes, self.capacity_bytes - used, self.capacity_bytes
+            ))
+            .into());
+        }
+        if let Some(limit) = self.domain_limits.for_domain(&req.security_domain) {
+            let domain_used = domain_bytes(&allocations, &req.security_domain);
+            if domain_used.saturating_add(req.bytes) > limit {
+                return Err(FfmError::InsufficientCapacity(format!(
+                    "insufficient capacity: {} bytes requested, security_domain '{}' has {} of its {} bytes left",
+                    req.bytes, req.security_domain, limit.saturating_sub(domain_used), limit
+                ))
+                .into());
+            }
+        }
+        let mut next_id = self.next_id.write().await;
+        let id = format!("ffm-{:04x}", *next_id);
+        *next_id += 1;
+
+        let handle = FfmHandle {
+            id: id.clone(),
+            bytes: req.bytes,
+            latency_class: req.latency_class,
+            bandwidth_floor_GBs: req.bandwidth_floor_GBs,
+            persistence: req.persistence,
+            shareable: req.shareable,
+            security_domain: req.security_domain,
+            created_at: chrono::Utc::now(),
+        };
+        allocations.insert(id, handle.clone());
+        self.update_domain_metrics(&allocations, &handle.security_domain);
+        Ok(handle)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<FfmHandle> {
+        let allocations = self.allocations.read().await;
+        allocations.get(id).cloned().ok_or_else(|| FfmError::NotFound(id.to_string()).into())
+    }
+
+    pub async fn release(&self, id: &str) -> Result<FfmHandle> {
+        let mut allocations = self.allocations.write().await;
+        let handle = allocations.remove(id).ok_or_else(|| FfmError::NotFound(id.to_string()))?;
+        self.update_domain_metrics(&allocations, &handle.security_domain);
+        Ok(handle)
+    }
+}
+
+fn domain_bytes(allocations: &HashMap<String, FfmHandle>, domain: &str) -> u64 {
+    allocations.values().filter(|h| h.security_domain == domain).map(|h| h.bytes).sum()
+}
+
+fn error_reply(e: anyhow::Error) -> warp::reply::WithStatus<warp::reply::Json> {
+    let e = FfmError::from(e);
+    warp::reply::with_status(warp::reply::json(&serde_json::json!({"error": e.to_string()})), e.status())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let service = Arc::new(FfmService::new()?);
+
+    let cors = warp::cors()
+        .allow_any_origin()
+        .allow_headers(vec!["content-type"])
+        .allow_methods(vec!["GET", "POST", "DELETE"]);
+
+    let health = warp::path("health")
+        .and(warp::get())
+        .map(|| warp::reply::json(&serde_json::json!({"status": "ok"})));
+
+    // Allocate endpoint
+    let service1 = service.clone();
+    let allocate = warp::path!("v1" / "ffm")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(warp::any().map(move || service1.clone()))
+        .and_then(|req: FfmAllocateRequest, service: Arc<FfmService>| async move {
+            match service.allocate(req).await {
+                Ok(handle) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&handle),
+                    warp::http::StatusCode::CREATED,
+                )),
+                Err(e) => Ok(error_reply(e)),
+            }
+        });
+
+    // Get allocation endpoint
+    let service2 = service.clone();
+    let get_allocation = warp::path!("v1" / "ffm" / String)
+        .and(warp::get())
+        .and(warp::any().map(move || service2.clone()))
+        .and_then(|id: String, service: Arc<FfmService>| async move {
+            match service.get(&id).await {
+                Ok(handle) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&handle),
+                    warp::http::StatusCode::OK,
+                )),
+                Err(e) => Ok(error_reply(e)),
+            }
+        });
+
+    // Release allocation endpoint
+    let service3 = service.clone();
+    let release = warp::path!("v1" / "ffm" / String)
+        .and(warp::delete())
+        .and(warp::any().map(move || service3.clone()))
+        .and_then(|id: String, service: Arc<FfmService>| async move {
+            match service.release(&id).await {
+                Ok(handle) => Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&handle),
+                    warp::http::StatusCode::OK,
+                )),
+                Err(e) => Ok(error_reply(e)),
+            }
+        });
+
+    let service4 = service.clone();
+    let metrics = warp::path!("metrics")
+        .and(warp::get())
+        .and(warp::any().map(move || service4.clone()))
+        .map(|service: Arc<FfmService>| match service.metrics() {
+            Ok(body) => warp::reply::with_status(body, warp::http::StatusCode::OK),
+            Err(e) => warp::reply::with_status(e.to_string(), warp::http::StatusCode::INTERNAL_SERVER_ERROR),
+        });
+
+    let routes = health.or(allocate).or(get_allocation).or(release).or(metrics).with(cors);
+
+    println!("Starting CorridorOS memd daemon on :8081");
+    warp::serve(routes).run(([0, 0, 0, 0], 8081)).await;
+
+    Ok(())
+}
@@ -54,7 +54,7 @@ class CorridorHandler(http.server.BaseHTTPRequestHandler):
                 "latency_budget_ns": request_data.get("latency_budget_ns", 250),
                 "reach_mm": request_data.get("reach_mm", 75),
                 "mode": "waveguide",
-                "qos": request_data.get("qos", {"pfc": True, "priority": "gold"}),
+                "qos": request_data.get("qos", {"pfc": True, "priority": "high"}),
                 "attestation_required": request_data.get("attestation_required", True),
                 "achievable_gbps": request_data.get("lanes", 8) * 52,  # 52 Gbps per lane
                 "ber": 1.0e-12,
@@ -0,0 +1,198 @@
+//! `corridorctl`: drive corrd from the command line through the SDK `Client`.
+
+use clap::{Parser, Subcommand};
+use corridoros_sdk::{Client, Corridor, CorridorAllocateRequest, RecalibrateRequest, RecalibrateResponse, TelemetryData};
+use serde::Serialize;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "corridorctl", about = "Allocate, inspect and recalibrate corrd corridors")]
+struct Cli {
+    /// corrd base URL.
+    #[arg(long, env = "CORRD_URL", default_value = "http://localhost:8080", global = true)]
+    url: String,
+    /// Print a table instead of JSON.
+    #[arg(long, global = true)]
+    pretty: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Allocate a corridor over consecutive wavelengths starting at --lambda-start.
+    Allocate {
+        #[arg(long = "type", default_value = "si_corridor")]
+        corridor_type: String,
+        #[arg(long, default_value_t = 1)]
+        lanes: u32,
+        #[arg(long, default_value_t = 1550)]
+        lambda_start: u32,
+        #[arg(long, default_value_t = 100)]
+        min_gbps: u32,
+        #[arg(long, default_value_t = 250)]
+        latency_ns: u32,
+        #[arg(long, default_value_t = 75)]
+        reach_mm: u32,
+        #[arg(long, default_value = "normal")]
+        priority: String,
+        #[arg(long)]
+        pfc: bool,
+        /// Require attestation and send this ticket.
+        #[arg(long)]
+        attestation_ticket: Option<String>,
+    },
+    /// Show one corridor.
+    Get { id: String },
+    /// List corridors (first page).
+    List,
+    /// Show current telemetry for a corridor.
+    Telemetry { id: String },
+    /// Recalibrate a corridor through HELIOPASS.
+    Recalibrate {
+        id: String,
+        #[arg(long)]
+        target_ber: f64,
+        #[arg(long, default_value = "lab")]
+        ambient_profile: String,
+    },
+    /// Deallocate a corridor.
+    Delete { id: String },
+}
+
+/// Rows for `--pretty`: a header and one line per record.
+trait Table {
+    fn header() -> Vec<&'static str>;
+    fn row(&self) -> Vec<String>;
+}
+
+impl Table for Corridor {
+    fn header() -> Vec<&'static str> {
+        vec!["ID", "STATUS", "TYPE", "LANES", "MIN_GBPS", "GBPS", "BER", "EYE", "OWNER"]
+    }
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.status.clone(),
+            self.corridor_type.clone(),
+            self.lanes.to_string(),
+            self.min_gbps.to_string(),
+            self.achievable_gbps.to_string(),
+            format!("{:.2e}", self.ber),
+            self.eye_margin.clone(),
+            self.created_by.clone(),
+        ]
+    }
+}
+
+impl Table for TelemetryData {
+    fn header() -> Vec<&'static str> {
+        vec!["BER", "TEMP_C", "PJ/BIT", "DRIFT", "UTIL%", "ERRORS"]
+    }
+    fn row(&self) -> Vec<String> {
+        vec![
+            format!("{:.2e}", self.ber),
+            format!("{:.1}", self.temp_c),
+            format!("{:.2}", self.power_pj_per_bit),
+            self.drift.clone(),
+            format!("{:.1}", self.utilization_percent),
+            self.error_count.to_string(),
+        ]
+    }
+}
+
+impl Table for RecalibrateResponse {
+    fn header() -> Vec<&'static str> {
+        vec!["STATUS", "CONVERGED", "TIME_MS", "FINAL_BER", "EYE", "SAVINGS%", "SOURCE"]
+    }
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.status.clone(),
+            self.converged.to_string(),
+            self.convergence_time_ms.to_string(),
+            format!("{:.2e}", self.final_ber),
+            format!("{:.2}", self.final_eye_margin),
+            format!("{:.1}", self.power_savings),
+            self.source.clone(),
+        ]
+    }
+}
+
+fn print_table<T: Table>(records: &[T]) {
+    let header = T::header();
+    let rows: Vec<Vec<String>> = records.iter().map(Table::row).collect();
+    let widths: Vec<usize> = header
+        .iter()
+        .enumerate()
+        .map(|(i, h)| rows.iter().map(|r| r[i].len()).chain([h.len()]).max().unwrap_or(0))
+        .collect();
+    let line = |cells: Vec<String>| {
+        let padded: Vec<String> = cells.iter().zip(&widths).map(|(c, w)| format!("{:<w$}", c, w = w)).collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+    line(header.iter().map(|h| h.to_string()).collect());
+    for row in rows {
+        line(row);
+    }
+}
+
+fn print<T: Table + Serialize>(records: &[T], single: bool, pretty: bool) {
+    if pretty {
+        print_table(records);
+    } else if single {
+        println!("{}", serde_json::to_string_pretty(&records[0]).unwrap_or_default());
+    } else {
+        println!("{}", serde_json::to_string_pretty(records).unwrap_or_default());
+    }
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    let client = Client::new(cli.url);
+    match cli.command {
+        Command::Allocate {
+            corridor_type,
+            lanes,
+            lambda_start,
+            min_gbps,
+            latency_ns,
+            reach_mm,
+            priority,
+            pfc,
+            attestation_ticket,
+        } => {
+            let mut builder = CorridorAllocateRequest::builder()
+                .corridor_type(corridor_type)
+                .lanes(lanes)
+                .lambda_nm((lambda_start..lambda_start + lanes).collect::<Vec<_>>())
+                .min_gbps(min_gbps)
+                .latency_budget_ns(latency_ns)
+                .reach_mm(reach_mm)
+                .priority(priority)
+                .pfc(pfc);
+            if let Some(ticket) = attestation_ticket {
+                builder = builder.attestation_ticket(ticket);
+            }
+            let corridor = client.allocate_corridor(&builder.build()?)?;
+            print(&[corridor], true, cli.pretty);
+        }
+        Command::Get { id } => print(&[client.get_corridor(&id).map_err(|e| e.to_string())?], true, cli.pretty),
+        Command::List => print(&client.list_corridors().map_err(|e| e.to_string())?.items, false, cli.pretty),
+        Command::Telemetry { id } => print(&[client.get_telemetry(&id).map_err(|e| e.to_string())?], true, cli.pretty),
+        Command::Recalibrate { id, target_ber, ambient_profile } => {
+            let req = RecalibrateRequest { target_ber, ambient_profile };
+            print(&[client.recalibrate(&id, &req).map_err(|e| e.to_string())?], true, cli.pretty)
+        }
+        Command::Delete { id } => print(&[client.delete_corridor(&id).map_err(|e| e.to_string())?], true, cli.pretty),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("corridorctl: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
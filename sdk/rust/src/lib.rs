@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QoSConfig { pub pfc: bool, pub priority: String }
@@ -8,6 +11,9 @@ pub struct CorridorAllocateRequest {
     pub corridor_type: String,
     pub lanes: u32,
     pub lambda_nm: Vec<u32>,
+    /// Wavelengths per lane when lanes are wavelength-multiplexed; `None` means one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lambdas_per_lane: Option<u32>,
     pub min_gbps: u32,
     pub latency_budget_ns: u32,
     pub reach_mm: u32,
@@ -17,9 +23,146 @@ pub struct CorridorAllocateRequest {
     pub attestation_ticket: Option<String>,
 }
 
+impl CorridorAllocateRequest {
+    pub fn builder() -> CorridorAllocateRequestBuilder { CorridorAllocateRequestBuilder::default() }
+}
+
+/// Chainable construction of a `CorridorAllocateRequest`. Defaults to a single
+/// `si_corridor` waveguide lane with PFC off and no attestation; `lambda_nm`,
+/// `min_gbps` and `reach_mm` have no default and must be set before `build`.
+#[derive(Debug, Clone)]
+pub struct CorridorAllocateRequestBuilder {
+    corridor_type: String,
+    lanes: u32,
+    lambda_nm: Vec<u32>,
+    lambdas_per_lane: Option<u32>,
+    min_gbps: u32,
+    latency_budget_ns: u32,
+    reach_mm: u32,
+    mode: String,
+    qos: QoSConfig,
+    attestation_required: bool,
+    attestation_ticket: Option<String>,
+}
+
+impl Default for CorridorAllocateRequestBuilder {
+    fn default() -> Self {
+        Self {
+            corridor_type: "si_corridor".to_string(),
+            lanes: 1,
+            lambda_nm: Vec::new(),
+            lambdas_per_lane: None,
+            min_gbps: 0,
+            latency_budget_ns: 250,
+            reach_mm: 0,
+            mode: "waveguide".to_string(),
+            qos: QoSConfig { pfc: false, priority: "normal".to_string() },
+            attestation_required: false,
+            attestation_ticket: None,
+        }
+    }
+}
+
+impl CorridorAllocateRequestBuilder {
+    pub fn corridor_type(mut self, t: impl Into<String>) -> Self { self.corridor_type = t.into(); self }
+    pub fn lanes(mut self, lanes: u32) -> Self { self.lanes = lanes; self }
+    pub fn lambda_nm(mut self, lambda_nm: impl Into<Vec<u32>>) -> Self { self.lambda_nm = lambda_nm.into(); self }
+    pub fn lambdas_per_lane(mut self, n: u32) -> Self { self.lambdas_per_lane = Some(n); self }
+    pub fn min_gbps(mut self, gbps: u32) -> Self { self.min_gbps = gbps; self }
+    pub fn latency_budget_ns(mut self, ns: u32) -> Self { self.latency_budget_ns = ns; self }
+    pub fn reach_mm(mut self, mm: u32) -> Self { self.reach_mm = mm; self }
+    pub fn mode(mut self, mode: impl Into<String>) -> Self { self.mode = mode.into(); self }
+    pub fn pfc(mut self, pfc: bool) -> Self { self.qos.pfc = pfc; self }
+    pub fn priority(mut self, priority: impl Into<String>) -> Self { self.qos.priority = priority.into(); self }
+    /// Require attestation and pass `ticket` with the request.
+    pub fn attestation_ticket(mut self, ticket: impl Into<String>) -> Self {
+        self.attestation_required = true;
+        self.attestation_ticket = Some(ticket.into());
+        self
+    }
+
+    pub fn build(self) -> Result<CorridorAllocateRequest, String> {
+        if self.lambda_nm.is_empty() { return Err("lambda_nm must not be empty".to_string()); }
+        if self.min_gbps == 0 { return Err("min_gbps must be at least 1".to_string()); }
+        if self.reach_mm == 0 { return Err("reach_mm must be at least 1".to_string()); }
+        if self.lanes == 0 { return Err("lanes must be positive".to_string()); }
+        let per_lane = self.lambdas_per_lane.unwrap_or(1);
+        if per_lane == 0 { return Err("lambdas_per_lane must be positive".to_string()); }
+        if self.lambda_nm.len() != (self.lanes * per_lane) as usize {
+            return Err(format!("{} wavelengths given for {} lanes of {}", self.lambda_nm.len(), self.lanes, per_lane));
+        }
+        if self.corridor_type.is_empty() { return Err("corridor_type must not be empty".to_string()); }
+        if self.mode.is_empty() { return Err("mode must not be empty".to_string()); }
+        Ok(CorridorAllocateRequest {
+            corridor_type: self.corridor_type,
+            lanes: self.lanes,
+            lambda_nm: self.lambda_nm,
+            lambdas_per_lane: self.lambdas_per_lane,
+            min_gbps: self.min_gbps,
+            latency_budget_ns: self.latency_budget_ns,
+            reach_mm: self.reach_mm,
+            mode: self.mode,
+            qos: self.qos,
+            attestation_required: self.attestation_required,
+            attestation_ticket: self.attestation_ticket,
+        })
+    }
+}
+
+/// An allocated corridor. Fields past `status` default when an older corrd omits them.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Corridor { pub id: String, pub status: String }
+pub struct Corridor {
+    pub id: String,
+    pub status: String,
+    #[serde(default)]
+    pub corridor_type: String,
+    #[serde(default)]
+    pub lanes: u32,
+    #[serde(default)]
+    pub lambda_nm: Vec<u32>,
+    #[serde(default)]
+    pub min_gbps: u32,
+    #[serde(default)]
+    pub achievable_gbps: u32,
+    #[serde(default)]
+    pub ber: f64,
+    #[serde(default)]
+    pub eye_margin: String,
+    #[serde(default)]
+    pub created_by: String,
+}
+
+/// One page of `GET /v1/corridors`; `total` counts every match before paging.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorridorPage { pub items: Vec<Corridor>, pub total: usize, pub offset: usize }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryData {
+    pub ber: f64,
+    pub temp_c: f64,
+    pub power_pj_per_bit: f64,
+    pub drift: String,
+    pub utilization_percent: f64,
+    pub error_count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecalibrateRequest { pub target_ber: f64, pub ambient_profile: String }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecalibrateResponse {
+    pub status: String,
+    pub converged: bool,
+    pub convergence_time_ms: u64,
+    pub final_ber: f64,
+    pub final_eye_margin: f64,
+    pub power_savings: f64,
+    /// `heliopass` or `fallback`.
+    #[serde(default)]
+    pub source: String,
+}
+
+#[allow(non_snake_case)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FfmAllocateRequest {
     pub bytes: u64,
@@ -35,16 +178,411 @@ pub struct FfmAllocateRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FfmHandle { pub id: String, pub bytes: u64 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityLimits { pub max_reach_mm: HashMap<String, u32> }
+
+/// The wire name for a `corridor_type` spelling corrd accepts (`si`, `SiCorridor`,
+/// `si_corridor`, ...), mirroring its parser. `None` for anything else.
+pub fn canonical_corridor_type(t: &str) -> Option<&'static str> {
+    match t {
+        "si" | "SiCorridor" | "si_corridor" => Some("si_corridor"),
+        "carbon" | "CarbonCorridor" | "carbon_corridor" => Some("carbon_corridor"),
+        _ => None,
+    }
+}
+
+/// Server feature set from `GET /v1/capabilities`, used to validate requests client-side.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub corridor_types: Vec<String>,
+    pub modulation_formats: Vec<String>,
+    pub lane_rates_gbps: Vec<u32>,
+    pub bands: Vec<String>,
+    pub optimize: Vec<String>,
+    pub limits: CapabilityLimits,
+}
+
+impl Capabilities {
+    pub fn supports_modulation(&self, modulation: &str) -> bool {
+        self.modulation_formats.iter().any(|m| m.eq_ignore_ascii_case(modulation))
+    }
+
+    pub fn check_modulation(&self, modulation: &str) -> Result<(), ClientError> {
+        if self.supports_modulation(modulation) { return Ok(()); }
+        Err(ClientError::Unsupported(format!("modulation {} not supported, server offers {}", modulation, self.modulation_formats.join(","))))
+    }
+
+    /// Reject requests the server would refuse for its type or reach limits. Type
+    /// names are compared through `canonical_corridor_type`, so any accepted
+    /// spelling matches whichever form the server advertises.
+    pub fn check_request(&self, r: &CorridorAllocateRequest) -> Result<(), ClientError> {
+        let canonical = |t: &str| canonical_corridor_type(t).map(str::to_string).unwrap_or_else(|| t.to_string());
+        let wanted = canonical(&r.corridor_type);
+        if !self.corridor_types.iter().any(|t| canonical(t) == wanted) {
+            return Err(ClientError::Unsupported(format!("corridor_type {} not supported, server offers {}", r.corridor_type, self.corridor_types.join(","))));
+        }
+        let max_reach = self.limits.max_reach_mm.iter().find(|(t, _)| canonical(t) == wanted).map(|(_, max)| max);
+        match max_reach {
+            Some(max) if r.reach_mm > *max => Err(ClientError::Unsupported(format!("reach_mm {} exceeds the {} limit of {} mm", r.reach_mm, r.corridor_type, max))),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientError {
+    /// Connection or transport failure.
+    Network(String),
+    /// Response body did not match the expected type.
+    Deserialize(String),
+    /// Non-2xx response with its body.
+    Http { status: u16, body: String },
+    /// Request rejected client-side against server capabilities.
+    Unsupported(String),
+    /// This build has no HTTP transport.
+    Offline,
+    /// `wait_until_active` ran out of time.
+    Timeout(String),
+    /// The corridor went to `Error` while being waited on.
+    CorridorFailed(String),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Network(e) => write!(f, "network error: {}", e),
+            ClientError::Deserialize(e) => write!(f, "invalid response: {}", e),
+            ClientError::Http { status, body } => write!(f, "HTTP {}: {}", status, body),
+            ClientError::Unsupported(e) => write!(f, "unsupported request: {}", e),
+            ClientError::Offline => write!(f, "not implemented in minimal offline SDK"),
+            ClientError::Timeout(e) => write!(f, "timed out: {}", e),
+            ClientError::CorridorFailed(e) => write!(f, "corridor failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
 #[derive(Debug, Clone)]
 pub struct Client { pub base_url: String }
 
+/// Gap between status polls in `wait_until_active`: `initial`, doubling up to `max`.
+#[derive(Debug, Clone, Copy)]
+pub struct PollInterval { pub initial: Duration, pub max: Duration }
+
+impl Default for PollInterval {
+    fn default() -> Self { Self { initial: Duration::from_millis(250), max: Duration::from_secs(2) } }
+}
+
+/// Where a wait stands after one poll: `None` to keep polling. Older corrd
+/// releases report capitalized statuses.
+fn settled(c: Corridor) -> Option<Result<Corridor, ClientError>> {
+    match c.status.as_str() {
+        "active" | "Active" => Some(Ok(c)),
+        "error" | "Error" => Some(Err(ClientError::CorridorFailed(format!("{} entered Error", c.id)))),
+        _ => None,
+    }
+}
+
+/// The pause before the next poll, or a timeout error once `deadline` has passed.
+fn next_poll(id: &str, status: &str, started: Instant, deadline: Instant, interval: Duration) -> Result<Duration, ClientError> {
+    let now = Instant::now();
+    if now >= deadline {
+        return Err(ClientError::Timeout(format!("{} still {} after {:?}", id, status, now - started)));
+    }
+    Ok(interval.min(deadline - now))
+}
+
+/// Pull the server's `{"error": ...}` message out of a failure body, if present.
+#[cfg(feature = "http")]
+fn error_message(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(str::to_string))
+        .unwrap_or_else(|| body.to_string())
+}
+
 impl Client {
     pub fn new(base: impl Into<String>) -> Self { Self{ base_url: base.into() } }
-    pub fn allocate_corridor(&self, _r: &CorridorAllocateRequest) -> Result<Corridor, String> {
-        Err("not implemented in minimal offline SDK".to_string())
+    /// `POST /v1/corridors`.
+    pub fn allocate_corridor(&self, r: &CorridorAllocateRequest) -> Result<Corridor, String> {
+        self.post_json("/v1/corridors", r).map_err(|e| e.to_string())
+    }
+    /// `POST /v1/ffm`.
+    pub fn allocate_ffm(&self, r: &FfmAllocateRequest) -> Result<FfmHandle, String> {
+        self.post_json("/v1/ffm", r).map_err(|e| e.to_string())
+    }
+    /// Wraps `GET /v1/capabilities`.
+    pub fn capabilities(&self) -> Result<Capabilities, ClientError> {
+        self.get_json("/v1/capabilities")
+    }
+    /// `GET /v1/corridors/{id}`.
+    pub fn get_corridor(&self, id: &str) -> Result<Corridor, ClientError> {
+        self.get_json(&format!("/v1/corridors/{}", id))
+    }
+    /// First page of `GET /v1/corridors`, at the server's default page size.
+    pub fn list_corridors(&self) -> Result<CorridorPage, ClientError> {
+        self.get_json("/v1/corridors")
+    }
+    /// `GET /v1/corridors/{id}/telemetry`.
+    pub fn get_telemetry(&self, id: &str) -> Result<TelemetryData, ClientError> {
+        self.get_json(&format!("/v1/corridors/{}/telemetry", id))
+    }
+    /// `POST /v1/corridors/{id}/recalibrate`.
+    pub fn recalibrate(&self, id: &str, r: &RecalibrateRequest) -> Result<RecalibrateResponse, ClientError> {
+        self.post_json(&format!("/v1/corridors/{}/recalibrate", id), r)
+    }
+    /// `DELETE /v1/corridors/{id}`; returns the removed corridor.
+    pub fn delete_corridor(&self, id: &str) -> Result<Corridor, ClientError> {
+        self.delete_json(&format!("/v1/corridors/{}", id))
+    }
+    /// Poll `GET /v1/corridors/{id}` until it is `active`, at the default `PollInterval`.
+    pub fn wait_until_active(&self, id: &str, timeout: Duration) -> Result<Corridor, ClientError> {
+        self.wait_until_active_with(id, timeout, PollInterval::default())
+    }
+    /// `wait_until_active` with its own poll interval. Fails early with
+    /// `CorridorFailed` if the corridor goes to `Error`.
+    pub fn wait_until_active_with(&self, id: &str, timeout: Duration, poll: PollInterval) -> Result<Corridor, ClientError> {
+        let started = Instant::now();
+        let deadline = started + timeout;
+        let mut interval = poll.initial;
+        loop {
+            let corridor = self.get_corridor(id)?;
+            let status = corridor.status.clone();
+            if let Some(done) = settled(corridor) {
+                return done;
+            }
+            std::thread::sleep(next_poll(id, &status, started, deadline, interval)?);
+            interval = interval.saturating_mul(2).min(poll.max);
+        }
+    }
+
+    fn url(&self, path: &str) -> String { format!("{}{}", self.base_url.trim_end_matches('/'), path) }
+
+    #[cfg(feature = "http")]
+    fn post_json<B: Serialize, T: serde::de::DeserializeOwned>(&self, path: &str, body: &B) -> Result<T, ClientError> {
+        let resp = reqwest::blocking::Client::new().post(self.url(path)).json(body).send()
+            .map_err(|e| ClientError::Network(e.to_string()))?;
+        Self::decode(resp)
+    }
+
+    #[cfg(feature = "http")]
+    fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
+        let resp = reqwest::blocking::get(self.url(path)).map_err(|e| ClientError::Network(e.to_string()))?;
+        Self::decode(resp)
+    }
+
+    #[cfg(feature = "http")]
+    fn delete_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
+        let resp = reqwest::blocking::Client::new().delete(self.url(path)).send()
+            .map_err(|e| ClientError::Network(e.to_string()))?;
+        Self::decode(resp)
+    }
+
+    #[cfg(feature = "http")]
+    fn decode<T: serde::de::DeserializeOwned>(resp: reqwest::blocking::Response) -> Result<T, ClientError> {
+        let status = resp.status();
+        let body = resp.text().map_err(|e| ClientError::Network(e.to_string()))?;
+        if !status.is_success() {
+            return Err(ClientError::Http { status: status.as_u16(), body: error_message(&body) });
+        }
+        serde_json::from_str(&body).map_err(|e| ClientError::Deserialize(e.to_string()))
+    }
+
+    #[cfg(not(feature = "http"))]
+    fn post_json<B: Serialize, T: serde::de::DeserializeOwned>(&self, path: &str, _body: &B) -> Result<T, ClientError> {
+        let _ = self.url(path);
+        Err(ClientError::Offline)
+    }
+
+    #[cfg(not(feature = "http"))]
+    fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
+        let _ = self.url(path);
+        Err(ClientError::Offline)
+    }
+
+    #[cfg(not(feature = "http"))]
+    fn delete_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
+        let _ = self.url(path);
+        Err(ClientError::Offline)
+    }
+}
+
+
+/// Non-blocking client for use inside tokio services.
+#[cfg(feature = "http")]
+#[derive(Debug, Clone)]
+pub struct AsyncClient { pub base_url: String, http: reqwest::Client, max_reconnect_attempts: u32 }
+
+/// Reconnects a telemetry subscription tries in a row before giving up.
+#[cfg(feature = "http")]
+pub const DEFAULT_MAX_RECONNECT_ATTEMPTS: u32 = 5;
+
+#[cfg(feature = "http")]
+impl AsyncClient {
+    pub fn new(base: impl Into<String>) -> Self {
+        Self{ base_url: base.into(), http: reqwest::Client::new(), max_reconnect_attempts: DEFAULT_MAX_RECONNECT_ATTEMPTS }
+    }
+
+    /// Apply a per-request timeout. Fails if the HTTP client can't be built (for
+    /// example, no TLS backend), rather than silently dropping the timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Result<Self, ClientError> {
+        self.http = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| ClientError::Network(format!("build HTTP client failed: {}", e)))?;
+        Ok(self)
+    }
+
+    /// Cap consecutive reconnects for `subscribe_telemetry`; 0 ends the stream on the first drop.
+    pub fn with_max_reconnect_attempts(mut self, attempts: u32) -> Self {
+        self.max_reconnect_attempts = attempts;
+        self
+    }
+
+    pub async fn allocate_corridor(&self, r: &CorridorAllocateRequest) -> Result<Corridor, ClientError> {
+        self.send(self.http.post(self.url("/v1/corridors")).json(r)).await
+    }
+    pub async fn allocate_ffm(&self, r: &FfmAllocateRequest) -> Result<FfmHandle, ClientError> {
+        self.send(self.http.post(self.url("/v1/ffm")).json(r)).await
+    }
+    pub async fn get_telemetry(&self, id: &str) -> Result<TelemetryData, ClientError> {
+        self.send(self.http.get(self.url(&format!("/v1/corridors/{}/telemetry", id)))).await
+    }
+    pub async fn get_corridor(&self, id: &str) -> Result<Corridor, ClientError> {
+        self.send(self.http.get(self.url(&format!("/v1/corridors/{}", id)))).await
+    }
+    /// Async `Client::wait_until_active`.
+    pub async fn wait_until_active(&self, id: &str, timeout: Duration) -> Result<Corridor, ClientError> {
+        self.wait_until_active_with(id, timeout, PollInterval::default()).await
+    }
+    /// Async `Client::wait_until_active_with`.
+    pub async fn wait_until_active_with(&self, id: &str, timeout: Duration, poll: PollInterval) -> Result<Corridor, ClientError> {
+        let started = Instant::now();
+        let deadline = started + timeout;
+        let mut interval = poll.initial;
+        loop {
+            let corridor = self.get_corridor(id).await?;
+            let status = corridor.status.clone();
+            if let Some(done) = settled(corridor) {
+                return done;
+            }
+            tokio::time::sleep(next_poll(id, &status, started, deadline, interval)?).await;
+            interval = interval.saturating_mul(2).min(poll.max);
+        }
     }
-    pub fn allocate_ffm(&self, _r: &FfmAllocateRequest) -> Result<FfmHandle, String> {
-        Err("not implemented in minimal offline SDK".to_string())
+    /// First page of corridors, at the server's default page size.
+    pub async fn list_corridors(&self) -> Result<CorridorPage, ClientError> {
+        self.send(self.http.get(self.url("/v1/corridors"))).await
+    }
+
+    /// Frames from `GET /v1/corridors/{id}/telemetry/ws`. Dropped connections are
+    /// reopened with exponential backoff; the stream ends with an error once
+    /// `max_reconnect_attempts` consecutive reconnects fail, or the corridor is gone.
+    #[cfg(feature = "ws")]
+    pub fn subscribe_telemetry(&self, id: &str) -> impl futures_util::Stream<Item = Result<TelemetryData, ClientError>> + Send + Unpin {
+        let base = self.base_url.trim_end_matches('/');
+        let base = base.strip_prefix("http").map(|rest| format!("ws{}", rest)).unwrap_or_else(|| base.to_string());
+        let sub = ws::Subscription::new(format!("{}/v1/corridors/{}/telemetry/ws", base, id), self.max_reconnect_attempts);
+        Box::pin(futures_util::stream::unfold(sub, |mut sub| async move {
+            sub.next_frame().await.map(|item| (item, sub))
+        }))
+    }
+
+    fn url(&self, path: &str) -> String { format!("{}{}", self.base_url.trim_end_matches('/'), path) }
+
+    async fn send<T: serde::de::DeserializeOwned>(&self, req: reqwest::RequestBuilder) -> Result<T, ClientError> {
+        let resp = req.send().await.map_err(|e| ClientError::Network(e.to_string()))?;
+        let status = resp.status();
+        let body = resp.text().await.map_err(|e| ClientError::Network(e.to_string()))?;
+        if !status.is_success() {
+            return Err(ClientError::Http { status: status.as_u16(), body: error_message(&body) });
+        }
+        serde_json::from_str(&body).map_err(|e| ClientError::Deserialize(e.to_string()))
     }
 }
 
+#[cfg(feature = "ws")]
+mod ws {
+    use futures_util::StreamExt;
+    use std::time::Duration;
+    use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+
+    use crate::{error_message, ClientError, TelemetryData};
+
+    type Socket = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+    const BACKOFF_BASE: Duration = Duration::from_millis(200);
+    const BACKOFF_MAX: Duration = Duration::from_secs(5);
+
+    /// Socket state behind `AsyncClient::subscribe_telemetry`.
+    pub(crate) struct Subscription {
+        url: String,
+        socket: Option<Socket>,
+        max_attempts: u32,
+        /// Consecutive failed connections; reset by every frame received.
+        attempts: u32,
+        last_error: String,
+        done: bool,
+    }
+
+    impl Subscription {
+        pub(crate) fn new(url: String, max_attempts: u32) -> Self {
+            Self { url, socket: None, max_attempts, attempts: 0, last_error: String::new(), done: false }
+        }
+
+        pub(crate) async fn next_frame(&mut self) -> Option<Result<TelemetryData, ClientError>> {
+            loop {
+                if self.done {
+                    return None;
+                }
+                if self.socket.is_none() {
+                    if self.attempts > self.max_attempts {
+                        self.done = true;
+                        return Some(Err(ClientError::Network(format!(
+                            "{}: gave up after {} reconnect attempts: {}",
+                            self.url, self.max_attempts, self.last_error
+                        ))));
+                    }
+                    if self.attempts > 0 {
+                        let backoff = BACKOFF_BASE.saturating_mul(1 << (self.attempts - 1).min(16));
+                        tokio::time::sleep(backoff.min(BACKOFF_MAX)).await;
+                    }
+                    match tokio_tungstenite::connect_async(self.url.as_str()).await {
+                        Ok((socket, _)) => self.socket = Some(socket),
+                        // The server answered the handshake with an error (e.g. 404 once the
+                        // corridor is deallocated); retrying won't change that.
+                        Err(WsError::Http(resp)) => {
+                            self.done = true;
+                            let body = String::from_utf8_lossy(resp.body().as_deref().unwrap_or_default()).into_owned();
+                            return Some(Err(ClientError::Http { status: resp.status().as_u16(), body: error_message(&body) }));
+                        }
+                        Err(e) => {
+                            self.attempts += 1;
+                            self.last_error = e.to_string();
+                            continue;
+                        }
+                    }
+                }
+                let socket = self.socket.as_mut()?;
+                match socket.next().await {
+                    Some(Ok(Message::Text(text))) => {
+                        self.attempts = 0;
+                        return Some(serde_json::from_str(&text).map_err(|e| ClientError::Deserialize(e.to_string())));
+                    }
+                    Some(Ok(Message::Close(_))) | None => {
+                        self.socket = None;
+                        self.attempts += 1;
+                        self.last_error = "connection closed".to_string();
+                    }
+                    Some(Err(e)) => {
+                        self.socket = None;
+                        self.attempts += 1;
+                        self.last_error = e.to_string();
+                    }
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+}